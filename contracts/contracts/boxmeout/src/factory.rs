@@ -1,29 +1,97 @@
 // contract/src/factory.rs - Market Factory Contract Implementation
 // Handles market creation and lifecycle management
 
+// The `MarketFactoryArgs` helpers generated by #[contractimpl] don't inherit
+// per-fn or impl-level allows, so wide entrypoints are allowed module-wide.
+#![allow(clippy::too_many_arguments)]
+
+use crate::MAX_PAGE_LIMIT;
 use soroban_sdk::{
-    contract, contractevent, contractimpl, Address, Bytes, BytesN, Env, IntoVal, Symbol, Vec,
+    contract, contractevent, contractimpl, contracttype, token, Address, Bytes, BytesN, Env,
+    IntoVal, String, Symbol, Vec,
 };
 
-#[contractevent]
+#[contractevent(topics = ["factory_initialized_event", "v1"])]
 pub struct FactoryInitializedEvent {
     pub admin: Address,
     pub usdc: Address,
     pub treasury: Address,
 }
 
-#[contractevent]
+#[contractevent(topics = ["market_created_event", "v1"])]
 pub struct MarketCreatedEvent {
     pub market_id: BytesN<32>,
     pub creator: Address,
     pub closing_time: u64,
 }
 
+#[contractevent(topics = ["market_with_pool_created_event", "v1"])]
+pub struct MarketWithPoolCreatedEvent {
+    pub market_id: BytesN<32>,
+    pub creator: Address,
+    pub amm: Address,
+    pub initial_liquidity: u128,
+}
+
 // Storage keys
 const ADMIN_KEY: &str = "admin";
 const USDC_KEY: &str = "usdc";
 const TREASURY_KEY: &str = "treasury";
 const MARKET_COUNT_KEY: &str = "market_count";
+const ORACLE_KEY: &str = "oracle";
+const AMM_KEY: &str = "amm";
+const DECIMALS_KEY: &str = "decimals";
+const MAX_MARKET_DURATION_KEY: &str = "max_market_duration";
+const MARKET_ID_AT_KEY: &str = "market_id_at";
+const CREATION_DISCOUNT_KEY: &str = "creation_discount";
+
+/// Default cap on resolution_time - now for new markets (365 days)
+pub const DEFAULT_MAX_MARKET_DURATION: u64 = 31_536_000;
+
+/// Aggregate view of factory configuration
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FactoryConfig {
+    pub admin: Address,
+    pub usdc: Address,
+    pub treasury: Address,
+    pub market_count: u32,
+    pub oracle: Option<Address>,
+    pub amm: Option<Address>,
+    pub decimals: u32,
+    pub max_market_duration: u64,
+    pub creation_discount_step_bps: u32,
+    pub creation_discount_floor_bps: u32,
+}
+
+/// Market metadata recorded at creation
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketInfo {
+    pub market_id: BytesN<32>,
+    pub creator: Address,
+    pub title: Symbol,
+    /// Free-form text; Symbol is too short and charset-limited for prose
+    pub description: String,
+    pub category: Symbol,
+    pub resolution_criteria: String,
+    pub closing_time: u64,
+    pub resolution_time: u64,
+}
+
+/// How a resolved market was decided, combining oracle votes with the
+/// factory's record of when the resolution was recorded
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketResolutionDetail {
+    pub market_id: BytesN<32>,
+    pub winning_outcome: u32,
+    pub yes_count: u32,
+    pub no_count: u32,
+    pub voters: Vec<Address>,
+    /// None until record_market_resolved has been called for the market
+    pub resolved_at: Option<u64>,
+}
 
 /// MARKET FACTORY - Handles market creation, fee collection, and market registry
 #[contract]
@@ -60,6 +128,15 @@ impl MarketFactory {
             .persistent()
             .set(&Symbol::new(&env, TREASURY_KEY), &treasury);
 
+        // Cache the token's decimals so fees are charged in whole token units
+        let decimals = token::Client::new(&env, &usdc).decimals();
+        if 10i128.checked_pow(decimals).is_none() {
+            panic!("token decimals too large");
+        }
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, DECIMALS_KEY), &decimals);
+
         // Initialize market counter at 0
         env.storage()
             .persistent()
@@ -90,19 +167,145 @@ impl MarketFactory {
             .expect("Treasury not set")
     }
 
+    /// Get all factory configuration in a single call
+    pub fn get_config(env: Env) -> FactoryConfig {
+        let storage = env.storage().persistent();
+        let (creation_discount_step_bps, creation_discount_floor_bps): (u32, u32) = storage
+            .get(&Symbol::new(&env, CREATION_DISCOUNT_KEY))
+            .unwrap_or((0, 10000));
+        FactoryConfig {
+            admin: storage
+                .get(&Symbol::new(&env, ADMIN_KEY))
+                .expect("Admin not set"),
+            usdc: storage
+                .get(&Symbol::new(&env, USDC_KEY))
+                .expect("USDC not set"),
+            treasury: storage
+                .get(&Symbol::new(&env, TREASURY_KEY))
+                .expect("Treasury not set"),
+            market_count: storage
+                .get(&Symbol::new(&env, MARKET_COUNT_KEY))
+                .unwrap_or(0),
+            oracle: storage.get(&Symbol::new(&env, ORACLE_KEY)),
+            amm: storage.get(&Symbol::new(&env, AMM_KEY)),
+            decimals: storage.get(&Symbol::new(&env, DECIMALS_KEY)).unwrap_or(7),
+            max_market_duration: storage
+                .get(&Symbol::new(&env, MAX_MARKET_DURATION_KEY))
+                .unwrap_or(DEFAULT_MAX_MARKET_DURATION),
+            creation_discount_step_bps,
+            creation_discount_floor_bps,
+        }
+    }
+
     /// Create a new market instance
     pub fn create_market(
         env: Env,
         creator: Address,
         title: Symbol,
-        description: Symbol,
+        description: String,
         category: Symbol,
+        resolution_criteria: String,
         closing_time: u64,
         resolution_time: u64,
     ) -> BytesN<32> {
         // Require creator authentication
         creator.require_auth();
 
+        Self::create_market_record(
+            env,
+            creator,
+            title,
+            description,
+            category,
+            resolution_criteria,
+            closing_time,
+            resolution_time,
+        )
+    }
+
+    /// Create a market and seed its AMM pool in one transaction
+    ///
+    /// The creator funds `initial_liquidity` and receives the pool's LP tokens.
+    /// If the pool cannot be seeded the whole call reverts, so no market is left
+    /// without a pool.
+    pub fn create_market_with_pool(
+        env: Env,
+        creator: Address,
+        title: Symbol,
+        description: String,
+        category: Symbol,
+        resolution_criteria: String,
+        closing_time: u64,
+        resolution_time: u64,
+        initial_liquidity: u128,
+    ) -> BytesN<32> {
+        creator.require_auth();
+
+        let amm: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, AMM_KEY))
+            .expect("AMM not set");
+
+        let market_id = Self::create_market_record(
+            env.clone(),
+            creator.clone(),
+            title,
+            description,
+            category,
+            resolution_criteria,
+            closing_time,
+            resolution_time,
+        );
+
+        // A panic in the AMM aborts this invocation and rolls back the market
+        env.invoke_contract::<()>(
+            &amm,
+            &Symbol::new(&env, "create_pool"),
+            (creator.clone(), market_id.clone(), initial_liquidity).into_val(&env),
+        );
+
+        MarketWithPoolCreatedEvent {
+            market_id: market_id.clone(),
+            creator,
+            amm,
+            initial_liquidity,
+        }
+        .publish(&env);
+
+        market_id
+    }
+
+    /// Helper: Apply the creator's reputation discount to the full creation fee
+    fn discounted_creation_fee(env: &Env, creator: &Address, full_fee: i128) -> i128 {
+        let (step_bps, floor_bps): (u32, u32) = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, CREATION_DISCOUNT_KEY))
+            .unwrap_or((0, 10000));
+        let resolved: u32 = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(env, "creator_resolved"), creator.clone()))
+            .unwrap_or(0);
+
+        let fee_bps = 10000u32
+            .saturating_sub(step_bps.saturating_mul(resolved))
+            .max(floor_bps);
+        full_fee * fee_bps as i128 / 10000
+    }
+
+    /// Helper: Validate, charge the creation fee and register a new market
+    fn create_market_record(
+        env: Env,
+        creator: Address,
+        title: Symbol,
+        description: String,
+        category: Symbol,
+        resolution_criteria: String,
+        closing_time: u64,
+        resolution_time: u64,
+    ) -> BytesN<32> {
         // Validate closing_time > now and < resolution_time
         let current_time = env.ledger().timestamp();
         if closing_time <= current_time {
@@ -112,6 +315,16 @@ impl MarketFactory {
             panic!("invalid timestamps");
         }
 
+        // Reject markets that resolve too far in the future
+        let max_duration: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MAX_MARKET_DURATION_KEY))
+            .unwrap_or(DEFAULT_MAX_MARKET_DURATION);
+        if resolution_time - current_time > max_duration {
+            panic!("market duration too long");
+        }
+
         // Get market count and increment
         let market_count: u32 = env
             .storage()
@@ -127,43 +340,79 @@ impl MarketFactory {
         let hash = env.crypto().sha256(&hash_input);
         let market_id = BytesN::from_array(&env, &hash.to_array());
 
+        // Charge creation fee before any persistent writes so a failed charge
+        // leaves no orphan market record (1 whole token unit at the token's decimals)
+        let decimals: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, DECIMALS_KEY))
+            .unwrap_or(7);
+        let creation_fee: i128 = 10i128
+            .checked_pow(decimals)
+            .expect("token decimals too large"); // 1 whole token unit
+        let creation_fee = Self::discounted_creation_fee(&env, &creator, creation_fee);
+        let treasury_address: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, TREASURY_KEY))
+            .expect("Treasury address not set");
+
+        // Cross-contract call to Treasury using contract address
+        // This works because we're calling by address at runtime, not compile-time module reference
+        // (a fully discounted fee has nothing to deposit)
+        if creation_fee > 0 {
+            env.invoke_contract::<()>(
+                &treasury_address,
+                &Symbol::new(&env, "deposit_fees"),
+                (creator.clone(), creation_fee, Some(market_id.clone())).into_val(&env),
+            );
+        }
+
         // Store market in registry
         let market_key = (Symbol::new(&env, "market"), market_id.clone());
         env.storage().persistent().set(&market_key, &true);
 
         // Store market metadata
         let metadata_key = (Symbol::new(&env, "market_meta"), market_id.clone());
-        let metadata = (
-            creator.clone(),
-            title.clone(),
+        let metadata = MarketInfo {
+            market_id: market_id.clone(),
+            creator: creator.clone(),
+            title,
             description,
             category,
+            resolution_criteria,
             closing_time,
             resolution_time,
-        );
+        };
         env.storage().persistent().set(&metadata_key, &metadata);
 
-        // Increment market counter
+        // Index market under its creator, with a separate count for cheap reads
+        let creator_markets_key = (Symbol::new(&env, "creator_markets"), creator.clone());
+        let mut creator_markets: Vec<BytesN<32>> = env
+            .storage()
+            .persistent()
+            .get(&creator_markets_key)
+            .unwrap_or(Vec::new(&env));
+        creator_markets.push_back(market_id.clone());
         env.storage()
             .persistent()
-            .set(&Symbol::new(&env, MARKET_COUNT_KEY), &(market_count + 1));
-
-        // Charge creation fee (1 USDC = 10^7 stroops, assuming 7 decimals)
-        let creation_fee: i128 = 10_000_000; // 1 USDC
-        let treasury_address: Address = env
-            .storage()
+            .set(&creator_markets_key, &creator_markets);
+        let creator_count_key = (Symbol::new(&env, "creator_mkt_count"), creator.clone());
+        env.storage()
             .persistent()
-            .get(&Symbol::new(&env, TREASURY_KEY))
-            .expect("Treasury address not set");
+            .set(&creator_count_key, &creator_markets.len());
 
-        // Cross-contract call to Treasury using contract address
-        // This works because we're calling by address at runtime, not compile-time module reference
-        env.invoke_contract::<()>(
-            &treasury_address,
-            &Symbol::new(&env, "deposit_fees"),
-            (creator.clone(), creation_fee).into_val(&env),
+        // Index the market by creation order; market_count is the index length
+        env.storage().persistent().set(
+            &(Symbol::new(&env, MARKET_ID_AT_KEY), market_count),
+            &market_id,
         );
 
+        // Increment market counter
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, MARKET_COUNT_KEY), &(market_count + 1));
+
         // Emit MarketCreated event
         MarketCreatedEvent {
             market_id: market_id.clone(),
@@ -175,19 +424,277 @@ impl MarketFactory {
         market_id
     }
 
+    /// Admin: Set the maximum time (seconds) between market creation and resolution
+    pub fn set_max_market_duration(env: Env, max_duration: u64) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Admin not set");
+        admin.require_auth();
+
+        if max_duration == 0 {
+            panic!("invalid market duration");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, MAX_MARKET_DURATION_KEY), &max_duration);
+    }
+
+    /// Admin: Discount the creation fee by `step_bps` per resolved market a creator
+    /// has made, never below `floor_bps` of the full fee. A step of 0 disables it.
+    pub fn set_creation_discount(env: Env, step_bps: u32, floor_bps: u32) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Admin not set");
+        admin.require_auth();
+
+        if step_bps > 10000 || floor_bps > 10000 {
+            panic!("invalid discount");
+        }
+
+        env.storage().persistent().set(
+            &Symbol::new(&env, CREATION_DISCOUNT_KEY),
+            &(step_bps, floor_bps),
+        );
+    }
+
+    /// Credit a market's creator once its resolution is finalized by the oracle.
+    /// Anyone may call this; each market counts at most once.
+    pub fn record_market_resolved(env: Env, market_id: BytesN<32>) {
+        let info: MarketInfo = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "market_meta"), market_id.clone()))
+            .expect("Market not found");
+
+        let counted_key = (Symbol::new(&env, "resolved_counted"), market_id.clone());
+        if env.storage().persistent().has(&counted_key) {
+            panic!("market already counted");
+        }
+
+        let oracle: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ORACLE_KEY))
+            .expect("Oracle not set");
+        let finalized = env.invoke_contract::<bool>(
+            &oracle,
+            &Symbol::new(&env, "is_finalized"),
+            (market_id.clone(),).into_val(&env),
+        );
+        if !finalized {
+            panic!("market not resolved");
+        }
+
+        env.storage().persistent().set(&counted_key, &true);
+        env.storage().persistent().set(
+            &(Symbol::new(&env, "resolved_at"), market_id),
+            &env.ledger().timestamp(),
+        );
+        let resolved_key = (Symbol::new(&env, "creator_resolved"), info.creator);
+        let resolved: u32 = env.storage().persistent().get(&resolved_key).unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&resolved_key, &(resolved + 1));
+    }
+
+    /// Get the number of a creator's markets recorded as resolved
+    pub fn get_creator_resolved_count(env: Env, creator: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "creator_resolved"), creator))
+            .unwrap_or(0)
+    }
+
+    /// Admin: Set the oracle contract used for resolution status queries
+    pub fn set_oracle(env: Env, oracle: Address) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Admin not set");
+        admin.require_auth();
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, ORACLE_KEY), &oracle);
+    }
+
+    /// Admin: Set the AMM contract used for trading data queries
+    pub fn set_amm(env: Env, amm: Address) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Admin not set");
+        admin.require_auth();
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, AMM_KEY), &amm);
+    }
+
+    /// Get a market's cumulative trading volume from the AMM (0 if it has no pool)
+    pub fn get_market_volume(env: Env, market_id: BytesN<32>) -> u128 {
+        let amm: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, AMM_KEY))
+            .expect("AMM not set");
+
+        env.invoke_contract::<u128>(
+            &amm,
+            &Symbol::new(&env, "get_volume"),
+            (market_id,).into_val(&env),
+        )
+    }
+
+    /// Get a market's liquidity depth (YES + NO reserves) from the AMM (0 if it has no pool)
+    pub fn get_market_liquidity(env: Env, market_id: BytesN<32>) -> u128 {
+        let amm: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, AMM_KEY))
+            .expect("AMM not set");
+
+        let (yes_reserve, no_reserve, _, _, _) = env
+            .invoke_contract::<(u128, u128, u128, u32, u32)>(
+                &amm,
+                &Symbol::new(&env, "get_pool_state"),
+                (market_id,).into_val(&env),
+            );
+        yes_reserve + no_reserve
+    }
+
+    /// Get the winning outcome, vote counts and voting oracles of a resolved market
+    pub fn get_market_resolution_detail(env: Env, market_id: BytesN<32>) -> MarketResolutionDetail {
+        let oracle: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ORACLE_KEY))
+            .expect("Oracle not set");
+
+        if !env.invoke_contract::<bool>(
+            &oracle,
+            &Symbol::new(&env, "is_finalized"),
+            (market_id.clone(),).into_val(&env),
+        ) {
+            panic!("market not resolved");
+        }
+        let winning_outcome = env.invoke_contract::<u32>(
+            &oracle,
+            &Symbol::new(&env, "get_consensus_result"),
+            (market_id.clone(),).into_val(&env),
+        );
+        let (yes_count, no_count) = env.invoke_contract::<(u32, u32)>(
+            &oracle,
+            &Symbol::new(&env, "get_attestation_counts"),
+            (market_id.clone(),).into_val(&env),
+        );
+        let voters = env.invoke_contract::<Vec<Address>>(
+            &oracle,
+            &Symbol::new(&env, "get_voters"),
+            (market_id.clone(),).into_val(&env),
+        );
+
+        MarketResolutionDetail {
+            market_id: market_id.clone(),
+            winning_outcome,
+            yes_count,
+            no_count,
+            voters,
+            resolved_at: env
+                .storage()
+                .persistent()
+                .get(&(Symbol::new(&env, "resolved_at"), market_id)),
+        }
+    }
+
+    /// Check whether a market's resolution is under dispute (open oracle challenge)
+    pub fn is_resolution_disputed(env: Env, market_id: BytesN<32>) -> bool {
+        let oracle: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ORACLE_KEY))
+            .expect("Oracle not set");
+
+        env.invoke_contract::<bool>(
+            &oracle,
+            &Symbol::new(&env, "has_active_challenge"),
+            (market_id,).into_val(&env),
+        )
+    }
+
+    /// Get the creator of a market, if the market was created by this factory
+    pub fn get_market_creator(env: Env, market_id: BytesN<32>) -> Option<Address> {
+        let metadata_key = (Symbol::new(&env, "market_meta"), market_id);
+        let metadata: Option<MarketInfo> = env.storage().persistent().get(&metadata_key);
+        metadata.map(|info| info.creator)
+    }
+
     /// Get market info by market_id
-    pub fn get_market_info(_env: Env, _market_id: BytesN<32>) {
-        todo!("See get market info TODO above")
+    pub fn get_market_info(env: Env, market_id: BytesN<32>) -> MarketInfo {
+        let metadata_key = (Symbol::new(&env, "market_meta"), market_id);
+        env.storage()
+            .persistent()
+            .get(&metadata_key)
+            .expect("Market not found")
     }
 
-    /// Get all active markets (paginated)
-    pub fn get_active_markets(_env: Env, _offset: u32, _limit: u32) -> Vec<Symbol> {
-        todo!("See get active markets TODO above")
+    /// Get markets still open for trading
+    ///
+    /// Scans at most `limit` markets (capped at MAX_PAGE_LIMIT) in creation order
+    /// starting at index `offset` and returns those not yet past closing_time, so
+    /// a page may hold fewer than `limit` entries. Page by advancing `offset` by
+    /// `limit` until it reaches get_market_count.
+    pub fn get_active_markets(env: Env, offset: u32, limit: u32) -> Vec<BytesN<32>> {
+        let market_count: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_COUNT_KEY))
+            .unwrap_or(0);
+        let end = offset
+            .saturating_add(limit.min(MAX_PAGE_LIMIT))
+            .min(market_count);
+        let now = env.ledger().timestamp();
+
+        let mut page = Vec::new(&env);
+        for index in offset..end {
+            let market_id: BytesN<32> = env
+                .storage()
+                .persistent()
+                .get(&(Symbol::new(&env, MARKET_ID_AT_KEY), index))
+                .expect("Market not found");
+            let info: MarketInfo = env
+                .storage()
+                .persistent()
+                .get(&(Symbol::new(&env, "market_meta"), market_id.clone()))
+                .expect("Market not found");
+            if info.closing_time > now {
+                page.push_back(market_id);
+            }
+        }
+        page
     }
 
     /// Get user's created markets
-    pub fn get_creator_markets(_env: Env, _creator: Address) {
-        todo!("See get creator markets TODO above")
+    pub fn get_creator_markets(env: Env, creator: Address) -> Vec<BytesN<32>> {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "creator_markets"), creator))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the number of markets a user has created
+    pub fn get_creator_market_count(env: Env, creator: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "creator_mkt_count"), creator))
+            .unwrap_or(0)
     }
 
     /// Get market resolution
@@ -14,6 +14,13 @@ pub mod treasury;
 
 pub mod helpers;
 
+/// Schema version carried as the second topic of every contract event.
+/// Bump together with the `topics` of each `#[contractevent]` when payloads change.
+pub const EVENT_VERSION: &str = "v1";
+
+/// Maximum number of items returned by a paginated getter, whatever limit is requested
+pub const MAX_PAGE_LIMIT: u32 = 100;
+
 // Feature-gated exports for WASM builds
 #[cfg(feature = "market")]
 pub use market::*;
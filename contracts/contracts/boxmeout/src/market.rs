@@ -3,10 +3,10 @@
 
 use soroban_sdk::{
     contract, contracterror, contractevent, contractimpl, contracttype, token, Address, BytesN,
-    Env, Symbol, Vec,
+    Env, IntoVal, Symbol, Vec,
 };
 
-#[contractevent]
+#[contractevent(topics = ["market_initialized_event", "v1"])]
 pub struct MarketInitializedEvent {
     pub market_id: BytesN<32>,
     pub creator: Address,
@@ -16,34 +16,34 @@ pub struct MarketInitializedEvent {
     pub resolution_time: u64,
 }
 
-#[contractevent]
+#[contractevent(topics = ["commitment_made_event", "v1"])]
 pub struct CommitmentMadeEvent {
     pub user: Address,
     pub market_id: BytesN<32>,
     pub amount: i128,
 }
 
-#[contractevent]
+#[contractevent(topics = ["market_closed_event", "v1"])]
 pub struct MarketClosedEvent {
     pub market_id: BytesN<32>,
     pub timestamp: u64,
 }
 
-#[contractevent]
+#[contractevent(topics = ["market_resolved_event", "v1"])]
 pub struct MarketResolvedEvent {
     pub market_id: BytesN<32>,
     pub final_outcome: u32,
     pub timestamp: u64,
 }
 
-#[contractevent]
+#[contractevent(topics = ["winnings_claimed_event", "v1"])]
 pub struct WinningsClaimedEvent {
     pub user: Address,
     pub market_id: BytesN<32>,
     pub net_payout: i128,
 }
 
-#[contractevent]
+#[contractevent(topics = ["prediction_revealed_event", "v1"])]
 pub struct PredictionRevealedEvent {
     pub user: Address,
     pub market_id: BytesN<32>,
@@ -52,7 +52,14 @@ pub struct PredictionRevealedEvent {
     pub timestamp: u64,
 }
 
-#[contractevent]
+#[contractevent(topics = ["market_timed_out_event", "v1"])]
+pub struct MarketTimedOutEvent {
+    pub market_id: BytesN<32>,
+    pub resolution_time: u64,
+    pub timestamp: u64,
+}
+
+#[contractevent(topics = ["market_disputed_event", "v1"])]
 pub struct MarketDisputedEvent {
     pub user: Address,
     pub reason: Symbol,
@@ -87,6 +94,10 @@ const STATE_RESOLVED: u32 = 2;
 const STATE_DISPUTED: u32 = 3;
 const STATE_CANCELLED: u32 = 4;
 
+/// Time after resolution_time (30 days) after which a market without oracle
+/// consensus can be timed out and refunded
+pub const MAX_RESOLUTION_DEADLINE: u64 = 2_592_000;
+
 /// Error codes following Soroban best practices
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -296,6 +307,14 @@ impl PredictionMarket {
         user.require_auth();
 
         // Validate market is initialized
+        if !env
+            .storage()
+            .persistent()
+            .has(&Symbol::new(&env, MARKET_STATE_KEY))
+        {
+            return Err(MarketError::NotInitialized);
+        }
+
         let market_state: u32 = env
             .storage()
             .persistent()
@@ -393,6 +412,44 @@ impl PredictionMarket {
         Ok(())
     }
 
+    /// Helper: Transition an Open market past closing_time to Closed and emit
+    /// MarketClosed. Returns true if the market was closed by this call.
+    fn auto_close_if_expired(env: &Env) -> bool {
+        let state: Option<u32> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, MARKET_STATE_KEY));
+        let closing_time: Option<u64> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, CLOSING_TIME_KEY));
+        let (Some(STATE_OPEN), Some(closing_time)) = (state, closing_time) else {
+            return false;
+        };
+
+        let current_time = env.ledger().timestamp();
+        if current_time < closing_time {
+            return false;
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(env, MARKET_STATE_KEY), &STATE_CLOSED);
+
+        let market_id: BytesN<32> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, MARKET_ID_KEY))
+            .expect("Market ID not found");
+        MarketClosedEvent {
+            market_id,
+            timestamp: current_time,
+        }
+        .publish(env);
+
+        true
+    }
+
     /// Helper: Generate storage key for user commitment
     fn get_commit_key(env: &Env, user: &Address) -> (Symbol, Address) {
         (Symbol::new(env, COMMIT_PREFIX), user.clone())
@@ -986,7 +1043,8 @@ impl PredictionMarket {
     /// Get market summary data
     ///
     /// Returns current market state including status, timing, pool size, and resolution data.
-    /// This is a read-only function that requires no authentication.
+    /// Requires no authentication. An Open market past closing_time is lazily
+    /// transitioned to Closed (emitting MarketClosed) before the state is read.
     ///
     /// # Returns
     /// - status: Current market state (0=OPEN, 1=CLOSED, 2=RESOLVED)
@@ -995,6 +1053,9 @@ impl PredictionMarket {
     /// - participant_count: Number of pending commitments
     /// - winning_outcome: Final outcome if resolved (0=NO, 1=YES), None otherwise
     pub fn get_market_state(env: Env, _market_id: BytesN<32>) -> MarketState {
+        // Lazily close an Open market whose closing_time has passed
+        Self::auto_close_if_expired(&env);
+
         // Get market status
         let status: u32 = env
             .storage()
@@ -1049,6 +1110,45 @@ impl PredictionMarket {
         }
     }
 
+    /// Get the market's lifecycle phase derived from timestamps and stored state
+    ///
+    /// Returns one of "trading", "closed", "awaiting_resolution", "resolved" or "cancelled".
+    pub fn get_market_phase(env: Env, _market_id: BytesN<32>) -> Symbol {
+        let status: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market not initialized");
+
+        let phase = match status {
+            STATE_CANCELLED => "cancelled",
+            STATE_RESOLVED => "resolved",
+            _ => {
+                let closing_time: u64 = env
+                    .storage()
+                    .persistent()
+                    .get(&Symbol::new(&env, CLOSING_TIME_KEY))
+                    .expect("Closing time not found");
+                let resolution_time: u64 = env
+                    .storage()
+                    .persistent()
+                    .get(&Symbol::new(&env, RESOLUTION_TIME_KEY))
+                    .expect("Resolution time not found");
+
+                let current_time = env.ledger().timestamp();
+                if status == STATE_OPEN && current_time < closing_time {
+                    "trading"
+                } else if current_time < resolution_time {
+                    "closed"
+                } else {
+                    "awaiting_resolution"
+                }
+            }
+        };
+
+        Symbol::new(&env, phase)
+    }
+
     /// Get prediction records for a user in this market
     ///
     /// Returns commitment_hash, amount, status, predicted_outcome (if revealed).
@@ -1338,19 +1438,119 @@ impl PredictionMarket {
             panic!("Market already cancelled");
         }
 
+        Self::refund_participants(&env);
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, MARKET_STATE_KEY), &STATE_CANCELLED);
+
+        let timestamp = env.ledger().timestamp();
+
+        #[contractevent(topics = ["market_cancelled_event", "v1"])]
+        pub struct MarketCancelledEvent {
+            pub market_id: BytesN<32>,
+            pub creator: Address,
+            pub timestamp: u64,
+        }
+
+        MarketCancelledEvent {
+            market_id,
+            creator,
+            timestamp,
+        }
+        .publish(&env);
+    }
+
+    /// Cancel a market whose oracles never reached consensus
+    ///
+    /// Permissionless: once `resolution_time + MAX_RESOLUTION_DEADLINE` has passed
+    /// and the oracle still reports no consensus, anyone can move the market to
+    /// CANCELLED, refunding all participants.
+    ///
+    /// # Panics
+    /// * If the resolution deadline has not passed yet
+    /// * If the market is already resolved, disputed or cancelled
+    /// * If the oracle has reached consensus (market should be resolved instead)
+    pub fn timeout_market(env: Env, market_id: BytesN<32>) {
+        let stored_market_id: BytesN<32> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_ID_KEY))
+            .expect("Market not initialized");
+        if market_id != stored_market_id {
+            panic!("Market ID mismatch");
+        }
+
+        let current_time = env.ledger().timestamp();
+
+        let resolution_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, RESOLUTION_TIME_KEY))
+            .expect("Resolution time not found");
+
+        if current_time <= resolution_time + MAX_RESOLUTION_DEADLINE {
+            panic!("Resolution deadline not reached");
+        }
+
+        let state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market state not found");
+
+        if state == STATE_RESOLVED || state == STATE_DISPUTED {
+            panic!("Cannot cancel resolved market");
+        }
+        if state == STATE_CANCELLED {
+            panic!("Market already cancelled");
+        }
+
+        // Only markets the oracle could not settle may be timed out
+        let oracle_address: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ORACLE_KEY))
+            .expect("Oracle address not found");
+
+        let (consensus_reached, _outcome) = env.invoke_contract::<(bool, u32)>(
+            &oracle_address,
+            &Symbol::new(&env, "check_consensus"),
+            (market_id.clone(),).into_val(&env),
+        );
+        if consensus_reached {
+            panic!("Oracle consensus reached");
+        }
+
+        Self::refund_participants(&env);
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, MARKET_STATE_KEY), &STATE_CANCELLED);
+
+        MarketTimedOutEvent {
+            market_id,
+            resolution_time,
+            timestamp: current_time,
+        }
+        .publish(&env);
+    }
+
+    /// Helper: Refund every participant's commitment or revealed prediction
+    fn refund_participants(env: &Env) {
         let usdc: Address = env
             .storage()
             .persistent()
-            .get(&Symbol::new(&env, USDC_KEY))
+            .get(&Symbol::new(env, USDC_KEY))
             .expect("USDC token not found");
-        let token_client = token::TokenClient::new(&env, &usdc);
+        let token_client = token::TokenClient::new(env, &usdc);
         let contract = env.current_contract_address();
 
         let participants: Vec<Address> = env
             .storage()
             .persistent()
-            .get(&Symbol::new(&env, PARTICIPANTS_KEY))
-            .unwrap_or_else(|| Vec::new(&env));
+            .get(&Symbol::new(env, PARTICIPANTS_KEY))
+            .unwrap_or_else(|| Vec::new(env));
 
         let len = participants.len();
         for i in 0..len {
@@ -1361,39 +1561,20 @@ impl PredictionMarket {
                 }
                 env.storage()
                     .persistent()
-                    .remove(&Self::get_commit_key(&env, &user));
+                    .remove(&Self::get_commit_key(env, &user));
             } else if let Some(pred) = Self::test_get_prediction(env.clone(), user.clone()) {
                 if pred.amount > 0 {
                     token_client.transfer(&contract, &user, &pred.amount);
                 }
-                let pred_key = (Symbol::new(&env, PREDICTION_PREFIX), user.clone());
+                let pred_key = (Symbol::new(env, PREDICTION_PREFIX), user.clone());
                 env.storage().persistent().remove(&pred_key);
             }
         }
 
         env.storage().persistent().set(
-            &Symbol::new(&env, PARTICIPANTS_KEY),
-            &Vec::<Address>::new(&env),
+            &Symbol::new(env, PARTICIPANTS_KEY),
+            &Vec::<Address>::new(env),
         );
-        env.storage()
-            .persistent()
-            .set(&Symbol::new(&env, MARKET_STATE_KEY), &STATE_CANCELLED);
-
-        let timestamp = env.ledger().timestamp();
-
-        #[contractevent]
-        pub struct MarketCancelledEvent {
-            pub market_id: BytesN<32>,
-            pub creator: Address,
-            pub timestamp: u64,
-        }
-
-        MarketCancelledEvent {
-            market_id,
-            creator,
-            timestamp,
-        }
-        .publish(&env);
     }
 
     // --- TEST HELPERS (Not for production use, but exposed for integration tests) ---
@@ -2010,6 +2191,210 @@ mod tests {
         market_client.resolve_market(&market_id_bytes);
     }
 
+    // ============================================================================
+    // MARKET PHASE TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_get_market_phase_transitions() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let oracle_contract_id = env.register(MockOracle, ());
+
+        env.ledger().with_mut(|li| {
+            li.timestamp = 1000;
+        });
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &oracle_contract_id,
+            &2000,
+            &3000,
+        );
+        assert_eq!(
+            market_client.get_market_phase(&market_id_bytes),
+            Symbol::new(&env, "trading")
+        );
+
+        // Past closing time, even before close_market is called
+        env.ledger().with_mut(|li| {
+            li.timestamp = 2000;
+        });
+        assert_eq!(
+            market_client.get_market_phase(&market_id_bytes),
+            Symbol::new(&env, "closed")
+        );
+        market_client.close_market(&market_id_bytes);
+        assert_eq!(
+            market_client.get_market_phase(&market_id_bytes),
+            Symbol::new(&env, "closed")
+        );
+
+        env.ledger().with_mut(|li| {
+            li.timestamp = 3000;
+        });
+        assert_eq!(
+            market_client.get_market_phase(&market_id_bytes),
+            Symbol::new(&env, "awaiting_resolution")
+        );
+
+        market_client.resolve_market(&market_id_bytes);
+        assert_eq!(
+            market_client.get_market_phase(&market_id_bytes),
+            Symbol::new(&env, "resolved")
+        );
+    }
+
+    #[test]
+    fn test_get_market_phase_cancelled() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let oracle_contract_id = env.register(MockOracle, ());
+        let token_admin = Address::generate(&env);
+        let usdc_client = create_token_contract(&env, &token_admin);
+
+        let creator = Address::generate(&env);
+        market_client.initialize(
+            &market_id_bytes,
+            &creator,
+            &Address::generate(&env),
+            &usdc_client.address,
+            &oracle_contract_id,
+            &2000,
+            &3000,
+        );
+
+        market_client.cancel_market(&creator, &market_id_bytes);
+        assert_eq!(
+            market_client.get_market_phase(&market_id_bytes),
+            Symbol::new(&env, "cancelled")
+        );
+    }
+
+    // ============================================================================
+    // TIMEOUT MARKET TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_timeout_market_without_consensus_refunds() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let oracle_contract_id = env.register(MockOracle, ());
+        let oracle_client = MockOracleClient::new(&env, &oracle_contract_id);
+
+        let token_admin = Address::generate(&env);
+        let usdc_client = create_token_contract(&env, &token_admin);
+
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &usdc_client.address,
+            &oracle_contract_id,
+            &2000,
+            &3000,
+        );
+
+        // User commits funds while the market is open
+        let user = Address::generate(&env);
+        usdc_client.mint(&user, &500);
+        let commit_hash = BytesN::from_array(&env, &[3u8; 32]);
+        market_client.commit_prediction(&user, &commit_hash, &500);
+        assert_eq!(usdc_client.balance(&user), 0);
+
+        // Oracles never reach consensus
+        oracle_client.set_consensus_status(&false);
+
+        env.ledger().with_mut(|li| {
+            li.timestamp = 3000 + MAX_RESOLUTION_DEADLINE + 1;
+        });
+
+        // Anyone can time out the market
+        market_client.timeout_market(&market_id_bytes);
+
+        assert_eq!(
+            market_client.get_market_state_value(),
+            Some(STATE_CANCELLED)
+        );
+        assert_eq!(usdc_client.balance(&user), 500);
+    }
+
+    #[test]
+    #[should_panic(expected = "Resolution deadline not reached")]
+    fn test_timeout_market_before_deadline_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let oracle_contract_id = env.register(MockOracle, ());
+        let oracle_client = MockOracleClient::new(&env, &oracle_contract_id);
+
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &oracle_contract_id,
+            &2000,
+            &3000,
+        );
+
+        oracle_client.set_consensus_status(&false);
+
+        env.ledger().with_mut(|li| {
+            li.timestamp = 3000 + MAX_RESOLUTION_DEADLINE;
+        });
+
+        market_client.timeout_market(&market_id_bytes);
+    }
+
+    #[test]
+    #[should_panic(expected = "Market ID mismatch")]
+    fn test_timeout_market_wrong_id_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let oracle_contract_id = env.register(MockOracle, ());
+        let oracle_client = MockOracleClient::new(&env, &oracle_contract_id);
+
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &oracle_contract_id,
+            &2000,
+            &3000,
+        );
+
+        oracle_client.set_consensus_status(&false);
+
+        env.ledger().with_mut(|li| {
+            li.timestamp = 3000 + MAX_RESOLUTION_DEADLINE + 1;
+        });
+
+        market_client.timeout_market(&BytesN::from_array(&env, &[1; 32]));
+    }
+
     // ============================================================================
     // REVEAL PREDICTION TESTS
     // ============================================================================
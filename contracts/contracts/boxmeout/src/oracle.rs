@@ -2,49 +2,105 @@
 // Handles multi-source oracle consensus for market resolution
 
 use soroban_sdk::{
-    contract, contractevent, contractimpl, contracttype, Address, BytesN, Env, Symbol, Vec,
+    contract, contractevent, contractimpl, contracttype, token, Address, BytesN, Env, IntoVal,
+    Symbol, Vec,
 };
 
-#[contractevent]
+#[contractevent(topics = ["oracle_initialized_event", "v1"])]
 pub struct OracleInitializedEvent {
     pub admin: Address,
     pub required_consensus: u32,
 }
 
-#[contractevent]
+#[contractevent(topics = ["oracle_registered_event", "v1"])]
 pub struct OracleRegisteredEvent {
     pub oracle: Address,
     pub oracle_name: Symbol,
     pub timestamp: u64,
 }
 
-#[contractevent]
+#[contractevent(topics = ["oracle_deregistered_event", "v1"])]
 pub struct OracleDeregisteredEvent {
     pub oracle: Address,
     pub timestamp: u64,
 }
 
-#[contractevent]
+#[contractevent(topics = ["oracle_stake_posted_event", "v1"])]
+pub struct OracleStakePostedEvent {
+    pub oracle: Address,
+    pub amount: i128,
+    pub total_posted: i128,
+}
+
+#[contractevent(topics = ["oracle_stake_withdrawn_event", "v1"])]
+pub struct OracleStakeWithdrawnEvent {
+    pub oracle: Address,
+    pub amount: i128,
+    pub total_posted: i128,
+}
+
+#[contractevent(topics = ["oracle_epoch_rotated_event", "v1"])]
+pub struct OracleEpochRotatedEvent {
+    pub new_epoch: u32,
+    pub timestamp: u64,
+}
+
+#[contractevent(topics = ["consensus_threshold_updated", "v1"])]
+pub struct ConsensusThresholdUpdatedEvent {
+    pub new_threshold: u32,
+    pub old_threshold: u32,
+}
+
+#[contractevent(topics = ["market_registered_event", "v1"])]
 pub struct MarketRegisteredEvent {
     pub market_id: BytesN<32>,
     pub resolution_time: u64,
 }
 
-#[contractevent]
+#[contractevent(topics = ["attestation_submitted_event", "v1"])]
 pub struct AttestationSubmittedEvent {
     pub market_id: BytesN<32>,
     pub oracle: Address,
     pub attestation_result: u32,
 }
 
-#[contractevent]
+#[contractevent(topics = ["consensus_progress_event", "v1"])]
+pub struct ConsensusProgressEvent {
+    pub market_id: BytesN<32>,
+    pub yes_count: u32,
+    pub no_count: u32,
+    pub threshold: u32,
+}
+
+#[contractevent(topics = ["consensus_reached_event", "v1"])]
+pub struct ConsensusReachedEvent {
+    pub market_id: BytesN<32>,
+    pub outcome: u32,
+    pub timestamp: u64,
+}
+
+#[contractevent(topics = ["consensus_recomputed_event", "v1"])]
+pub struct ConsensusRecomputedEvent {
+    pub market_id: BytesN<32>,
+    pub reached: bool,
+    pub outcome: u32,
+    pub timestamp: u64,
+}
+
+#[contractevent(topics = ["resolution_finalized_event", "v1"])]
 pub struct ResolutionFinalizedEvent {
     pub market_id: BytesN<32>,
     pub final_outcome: u32,
     pub timestamp: u64,
 }
 
-#[contractevent]
+#[contractevent(topics = ["keeper_reward_failed_event", "v1"])]
+pub struct KeeperRewardFailedEvent {
+    pub market_id: BytesN<32>,
+    pub keeper: Address,
+}
+
+#[contractevent(topics = ["attestation_challenged_event", "v1"])]
 pub struct AttestationChallengedEvent {
     pub oracle: Address,
     pub challenger: Address,
@@ -52,7 +108,7 @@ pub struct AttestationChallengedEvent {
     pub challenge_reason: Symbol,
 }
 
-#[contractevent]
+#[contractevent(topics = ["challenge_resolved_event", "v1"])]
 pub struct ChallengeResolvedEvent {
     pub oracle: Address,
     pub challenger: Address,
@@ -68,12 +124,43 @@ const ORACLE_COUNT_KEY: &str = "oracle_count";
 const MARKET_RES_TIME_KEY: &str = "mkt_res_time"; // Market resolution time storage
 const ATTEST_COUNT_YES_KEY: &str = "attest_yes"; // Attestation count for YES outcome
 const ATTEST_COUNT_NO_KEY: &str = "attest_no"; // Attestation count for NO outcome
+const MARKET_OUTCOME_COUNT_KEY: &str = "mkt_outcomes"; // Number of valid outcomes per market (2 = binary)
+const TIE_POLICY_KEY: &str = "tie_policy"; // How a tie at the top is resolved (default "no_consensus")
+const CONSENSUS_MODE_KEY: &str = "consensus_mode"; // "leading" (default) or "first_to_threshold"
+const MARKET_CONSENSUS_MODE_KEY: &str = "mkt_cons_mode"; // Consensus mode snapshotted at registration
+const VOTE_WEIGHT_MODE_KEY: &str = "vote_weight_mode"; // "count" (default), "reputation" or "stake"
+const WEIGHTED_THRESHOLD_KEY: &str = "weighted_threshold"; // Total weight a winner needs in weighted modes
+const MARKET_WEIGHT_MODE_KEY: &str = "mkt_weight_mode"; // Vote weight mode snapshotted at registration
+const MARKET_WEIGHT_THRESHOLD_KEY: &str = "mkt_weight_thresh"; // Weighted threshold snapshotted at registration
+const STAKE_TOKEN_KEY: &str = "stake_token"; // Token oracles deposit as voting stake
+const POSTED_STAKE_KEY: &str = "posted_stake"; // Tokens each oracle has deposited as voting stake
+const VOTE_WEIGHTS_KEY: &str = "vote_weights"; // (accuracy, posted stake) snapshotted per vote
 const ADMIN_SIGNERS_KEY: &str = "admin_signers"; // Multi-sig admin addresses
 const REQUIRED_SIGNATURES_KEY: &str = "required_sigs"; // Required signatures for multi-sig
 const LAST_OVERRIDE_TIME_KEY: &str = "last_override"; // Timestamp of last emergency override
 const OVERRIDE_COOLDOWN_KEY: &str = "override_cooldown"; // Cooldown period in seconds (default 86400 = 24h)
 const CHALLENGE_STAKE_AMOUNT: i128 = 1000; // Minimum stake required to challenge
 const ORACLE_STAKE_KEY: &str = "oracle_stake"; // Oracle's staked amount
+const ORACLE_EPOCH_KEY: &str = "oracle_epoch"; // Current oracle-set rotation epoch
+const ORACLE_CONFIRMED_EPOCH_KEY: &str = "oracle_conf_epoch"; // Last epoch each oracle confirmed participation
+const MARKET_EPOCH_KEY: &str = "market_epoch"; // Epoch recorded at market registration
+const MARKET_THRESHOLD_KEY: &str = "market_threshold"; // Consensus threshold snapshotted at market registration
+const PENDING_CHALLENGES_KEY: &str = "pending_challenges"; // Index of unresolved (market_id, oracle) challenges
+const ORACLE_HISTORY_KEY: &str = "oracle_history"; // Per-oracle list of attested market ids
+const ATTEST_RATE_LIMIT_KEY: &str = "attest_rate_limit"; // (max attestations, window seconds); unset disables it
+const ORACLE_RATE_WINDOW_KEY: &str = "oracle_rate_window"; // Per-oracle (window start, attestations in window)
+const OVERRIDE_NONCE_KEY: &str = "override_nonce"; // Last nonce issued to an override proposal
+const OVERRIDE_PROPOSAL_KEY: &str = "override_proposal"; // Pending two-admin override per market
+const OVERRIDE_PROPOSAL_TTL: u64 = 3600; // Seconds a proposal stays confirmable
+const TREASURY_KEY: &str = "treasury"; // Treasury paying keeper rewards for finalization
+const SLASH_DESTINATION_KEY: &str = "slash_destination"; // "treasury" (default), "burn" or "redistribute"
+const SLASHED_FUNDS_KEY: &str = "slashed_funds"; // Slashed stake accumulated per destination
+const ADAPTIVE_QUORUM_KEY: &str = "adaptive_quorum"; // Quorum as bps of active oracles; unset/0 keeps the fixed threshold
+const MARKET_QUORUM_KEY: &str = "mkt_quorum"; // Adaptive quorum bps snapshotted at registration
+const ATTEST_GRACE_KEY: &str = "attest_grace"; // Seconds before resolution_time early-finalizable markets accept attestations
+const EARLY_FINALIZE_KEY: &str = "early_finalize"; // Per-market flag opting into the attestation grace window
+const MARKET_CATEGORY_KEY: &str = "mkt_category"; // Category restricting which oracles may attest a market
+const CATEGORY_ORACLES_KEY: &str = "category_oracles"; // Per-category allowlist of eligible oracles
 
 /// Attestation record for market resolution
 #[contracttype]
@@ -82,6 +169,7 @@ pub struct Attestation {
     pub attestor: Address,
     pub outcome: u32,
     pub timestamp: u64,
+    pub evidence_uri: Option<Symbol>,
 }
 
 /// Emergency override approval record
@@ -92,6 +180,18 @@ pub struct OverrideApproval {
     pub timestamp: u64,
 }
 
+/// Pending two-admin emergency override, confirmable only by exact nonce before expiry
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OverrideProposal {
+    pub nonce: u64,
+    pub proposer: Address,
+    pub market_id: BytesN<32>,
+    pub forced_outcome: u32,
+    pub justification_hash: BytesN<32>,
+    pub expires_at: u64,
+}
+
 /// Emergency override record for audit trail
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -116,6 +216,26 @@ pub struct Challenge {
     pub resolved: bool,
 }
 
+/// Aggregate view of oracle configuration
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OracleConfig {
+    pub admin: Address,
+    pub required_consensus: u32,
+    pub oracle_count: u32,
+    pub required_signatures: u32,
+    pub override_cooldown: u64,
+    pub tie_policy: Symbol,
+    pub consensus_mode: Symbol,
+    pub vote_weight_mode: Symbol,
+    pub weighted_threshold: i128,
+    pub adaptive_quorum: u32,
+    pub attest_grace: u64,
+    pub slash_destination: Symbol,
+    pub treasury: Option<Address>,
+    pub stake_token: Option<Address>,
+}
+
 /// ORACLE MANAGER - Manages oracle consensus
 #[contract]
 pub struct OracleManager;
@@ -127,6 +247,11 @@ impl OracleManager {
         // Verify admin signature
         admin.require_auth();
 
+        // A zero threshold would treat every market as resolved with no votes
+        if required_consensus == 0 {
+            panic!("invalid consensus threshold");
+        }
+
         // Store admin
         env.storage()
             .persistent()
@@ -224,6 +349,20 @@ impl OracleManager {
             .persistent()
             .set(&stake_key, &(CHALLENGE_STAKE_AMOUNT * 10)); // 10x challenge stake
 
+        // Oracle is confirmed for the current epoch on registration
+        let current_epoch: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ORACLE_EPOCH_KEY))
+            .unwrap_or(0);
+        let confirmed_epoch_key = (
+            Symbol::new(&env, ORACLE_CONFIRMED_EPOCH_KEY),
+            oracle.clone(),
+        );
+        env.storage()
+            .persistent()
+            .set(&confirmed_epoch_key, &current_epoch);
+
         // Store registration timestamp
         let timestamp_key = (Symbol::new(&env, "oracle_timestamp"), oracle.clone());
         env.storage()
@@ -246,21 +385,109 @@ impl OracleManager {
 
     /// Deregister an oracle node
     ///
-    /// TODO: Deregister Oracle
-    /// - Require admin authentication
-    /// - Validate oracle is registered
-    /// - Remove oracle from active_oracles list
-    /// - Mark as inactive (don't delete, keep for history)
-    /// - Prevent oracle from submitting new attestations
-    /// - Don't affect existing attestations
-    /// - Emit OracleDeregistered(oracle_address, timestamp)
-    pub fn deregister_oracle(_env: Env, _oracle: Address) {
-        todo!("See deregister oracle TODO above")
-    }
-
-    /// Register a market with its resolution time for attestation validation
+    /// The oracle is marked inactive rather than deleted so its history is
+    /// kept; it can no longer attest, but existing attestations still count.
+    pub fn deregister_oracle(env: Env, oracle: Address) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        let oracle_key = (Symbol::new(&env, "oracle"), oracle.clone());
+        let is_registered: bool = env.storage().persistent().get(&oracle_key).unwrap_or(false);
+        if !is_registered {
+            panic!("Oracle not registered");
+        }
+        env.storage().persistent().set(&oracle_key, &false);
+
+        let oracle_count: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ORACLE_COUNT_KEY))
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &Symbol::new(&env, ORACLE_COUNT_KEY),
+            &oracle_count.saturating_sub(1),
+        );
+
+        OracleDeregisteredEvent {
+            oracle,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+    }
+
+    /// Admin: Start a new oracle epoch
+    ///
+    /// Oracles must call `confirm_oracle_epoch` to attest markets registered in
+    /// the new epoch; oracles that don't re-confirm stay ineligible for them.
+    pub fn rotate_oracle_epoch(env: Env) -> u32 {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        let current_epoch: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ORACLE_EPOCH_KEY))
+            .unwrap_or(0);
+        let new_epoch = current_epoch + 1;
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, ORACLE_EPOCH_KEY), &new_epoch);
+
+        OracleEpochRotatedEvent {
+            new_epoch,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+
+        new_epoch
+    }
+
+    /// Oracle re-confirms participation for the current epoch
+    pub fn confirm_oracle_epoch(env: Env, oracle: Address) {
+        oracle.require_auth();
+
+        let oracle_key = (Symbol::new(&env, "oracle"), oracle.clone());
+        let is_registered: bool = env.storage().persistent().get(&oracle_key).unwrap_or(false);
+        if !is_registered {
+            panic!("Oracle not registered");
+        }
+
+        let current_epoch: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ORACLE_EPOCH_KEY))
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &(Symbol::new(&env, ORACLE_CONFIRMED_EPOCH_KEY), oracle),
+            &current_epoch,
+        );
+    }
+
+    /// Get current oracle epoch
+    pub fn get_oracle_epoch(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, ORACLE_EPOCH_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Register a market with its resolution time and outcome count (2 = binary)
+    /// for attestation validation.
     /// Must be called before oracles can submit attestations for this market.
-    pub fn register_market(env: Env, market_id: BytesN<32>, resolution_time: u64) {
+    pub fn register_market(
+        env: Env,
+        market_id: BytesN<32>,
+        resolution_time: u64,
+        outcome_count: u32,
+    ) {
         // Require admin authentication
         let admin: Address = env
             .storage()
@@ -269,12 +496,108 @@ impl OracleManager {
             .expect("Oracle not initialized");
         admin.require_auth();
 
+        if outcome_count < 2 {
+            panic!("Invalid outcome count");
+        }
+
+        // A market with fewer oracles than the threshold could never resolve
+        let oracle_count: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ORACLE_COUNT_KEY))
+            .unwrap_or(0);
+        let required_consensus: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, REQUIRED_CONSENSUS_KEY))
+            .unwrap_or(0);
+        if oracle_count < required_consensus {
+            panic!("not enough oracles");
+        }
+
         // Store market resolution time
         let market_key = (Symbol::new(&env, MARKET_RES_TIME_KEY), market_id.clone());
         env.storage()
             .persistent()
             .set(&market_key, &resolution_time);
 
+        // Record the oracle epoch this market belongs to
+        let current_epoch: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ORACLE_EPOCH_KEY))
+            .unwrap_or(0);
+        let market_epoch_key = (Symbol::new(&env, MARKET_EPOCH_KEY), market_id.clone());
+        env.storage()
+            .persistent()
+            .set(&market_epoch_key, &current_epoch);
+
+        // Snapshot the consensus threshold so later changes don't affect this market
+        let threshold: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, REQUIRED_CONSENSUS_KEY))
+            .unwrap_or(0);
+        let threshold_key = (Symbol::new(&env, MARKET_THRESHOLD_KEY), market_id.clone());
+        env.storage().persistent().set(&threshold_key, &threshold);
+
+        // Snapshot whether quorum adapts to active oracles; the count itself stays live
+        let quorum_bps: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADAPTIVE_QUORUM_KEY))
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &(Symbol::new(&env, MARKET_QUORUM_KEY), market_id.clone()),
+            &quorum_bps,
+        );
+
+        // Store the number of outcomes attestations are validated against
+        let outcome_count_key = (
+            Symbol::new(&env, MARKET_OUTCOME_COUNT_KEY),
+            market_id.clone(),
+        );
+        env.storage()
+            .persistent()
+            .set(&outcome_count_key, &outcome_count);
+
+        // Snapshot the consensus mode so later mode changes don't alter this market
+        let mode: Symbol = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CONSENSUS_MODE_KEY))
+            .unwrap_or(Symbol::new(&env, "leading"));
+        env.storage().persistent().set(
+            &(
+                Symbol::new(&env, MARKET_CONSENSUS_MODE_KEY),
+                market_id.clone(),
+            ),
+            &mode,
+        );
+
+        // Snapshot the vote weighting and its threshold alongside the consensus mode
+        let weight_mode: Symbol = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, VOTE_WEIGHT_MODE_KEY))
+            .unwrap_or(Symbol::new(&env, "count"));
+        let weighted_threshold: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, WEIGHTED_THRESHOLD_KEY))
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &(Symbol::new(&env, MARKET_WEIGHT_MODE_KEY), market_id.clone()),
+            &weight_mode,
+        );
+        env.storage().persistent().set(
+            &(
+                Symbol::new(&env, MARKET_WEIGHT_THRESHOLD_KEY),
+                market_id.clone(),
+            ),
+            &weighted_threshold,
+        );
+
         // Initialize attestation counts for this market
         let yes_count_key = (Symbol::new(&env, ATTEST_COUNT_YES_KEY), market_id.clone());
         let no_count_key = (Symbol::new(&env, ATTEST_COUNT_NO_KEY), market_id.clone());
@@ -295,6 +618,17 @@ impl OracleManager {
         env.storage().persistent().get(&market_key)
     }
 
+    /// Seconds until a market's resolution time (negative once it has passed)
+    pub fn get_resolution_countdown(env: Env, market_id: BytesN<32>) -> i64 {
+        let market_key = (Symbol::new(&env, MARKET_RES_TIME_KEY), market_id);
+        let resolution_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&market_key)
+            .expect("Market not registered");
+        resolution_time as i64 - env.ledger().timestamp() as i64
+    }
+
     /// Get attestation counts for a market
     pub fn get_attestation_counts(env: Env, market_id: BytesN<32>) -> (u32, u32) {
         let yes_count_key = (Symbol::new(&env, ATTEST_COUNT_YES_KEY), market_id.clone());
@@ -306,6 +640,34 @@ impl OracleManager {
         (yes_count, no_count)
     }
 
+    /// Get the number of attestations submitted for a market
+    pub fn get_attestation_count(env: Env, market_id: BytesN<32>) -> u32 {
+        let voters_key = (Symbol::new(&env, "voters"), market_id);
+        let voters: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&voters_key)
+            .unwrap_or(Vec::new(&env));
+        voters.len()
+    }
+
+    /// Get a market's attestation by submission index (0-based)
+    pub fn get_attestation_at(env: Env, market_id: BytesN<32>, index: u32) -> Attestation {
+        let voters_key = (Symbol::new(&env, "voters"), market_id.clone());
+        let voters: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&voters_key)
+            .unwrap_or(Vec::new(&env));
+        let oracle = voters.get(index).expect("attestation index out of range");
+
+        let attestation_key = (Symbol::new(&env, "attestation"), market_id, oracle);
+        env.storage()
+            .persistent()
+            .get(&attestation_key)
+            .expect("Attestation not found")
+    }
+
     /// Get attestation record for an oracle on a market
     pub fn get_attestation(
         env: Env,
@@ -321,7 +683,7 @@ impl OracleManager {
     /// Validates:
     /// - Caller is a trusted attestor (registered oracle)
     /// - Market is past resolution_time
-    /// - Outcome is valid (0=NO, 1=YES)
+    /// - Outcome is below the market's outcome count (0=NO, 1=YES for binary markets)
     /// - Oracle hasn't already attested
     pub fn submit_attestation(
         env: Env,
@@ -329,6 +691,7 @@ impl OracleManager {
         market_id: BytesN<32>,
         attestation_result: u32,
         _data_hash: BytesN<32>,
+        evidence_uri: Option<Symbol>,
     ) {
         // 1. Require oracle authentication
         oracle.require_auth();
@@ -340,6 +703,40 @@ impl OracleManager {
             panic!("Oracle not registered");
         }
 
+        // 2b. Oracle must have confirmed participation in the market's epoch
+        let market_epoch: u32 = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, MARKET_EPOCH_KEY), market_id.clone()))
+            .unwrap_or(0);
+        let confirmed_epoch: u32 = env
+            .storage()
+            .persistent()
+            .get(&(
+                Symbol::new(&env, ORACLE_CONFIRMED_EPOCH_KEY),
+                oracle.clone(),
+            ))
+            .unwrap_or(0);
+        if confirmed_epoch < market_epoch {
+            panic!("Oracle not confirmed for market epoch");
+        }
+
+        // 2c. Restricted-category markets only accept allowlisted oracles
+        if let Some(category) = env
+            .storage()
+            .persistent()
+            .get::<_, Symbol>(&(Symbol::new(&env, MARKET_CATEGORY_KEY), market_id.clone()))
+        {
+            let eligible: Vec<Address> = env
+                .storage()
+                .persistent()
+                .get(&(Symbol::new(&env, CATEGORY_ORACLES_KEY), category))
+                .unwrap_or(Vec::new(&env));
+            if !eligible.contains(&oracle) {
+                panic!("oracle not eligible for category");
+            }
+        }
+
         // 3. Validate market is registered and past resolution_time
         let market_key = (Symbol::new(&env, MARKET_RES_TIME_KEY), market_id.clone());
         let resolution_time: u64 = env
@@ -348,13 +745,25 @@ impl OracleManager {
             .get(&market_key)
             .expect("Market not registered");
 
+        // Early-finalizable markets accept attestations within the grace window
+        let earliest_time = if is_early_finalizable(&env, &market_id) {
+            let grace: u64 = env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, ATTEST_GRACE_KEY))
+                .unwrap_or(0);
+            resolution_time.saturating_sub(grace)
+        } else {
+            resolution_time
+        };
+
         let current_time = env.ledger().timestamp();
-        if current_time < resolution_time {
+        if current_time < earliest_time {
             panic!("Cannot attest before resolution time");
         }
 
-        // 4. Validate result is binary (0 or 1)
-        if attestation_result > 1 {
+        // 4. Validate result is one of the market's outcomes
+        if attestation_result >= market_outcome_count(&env, &market_id) {
             panic!("Invalid attestation result");
         }
 
@@ -364,16 +773,40 @@ impl OracleManager {
             panic!("Oracle already attested");
         }
 
+        // 5b. Enforce per-oracle attestation rate limit
+        consume_attestation_quota(&env, &oracle, current_time);
+
         // 6. Store vote for consensus
         env.storage()
             .persistent()
             .set(&vote_key, &attestation_result);
 
+        // 6b. Snapshot the oracle's weights so later changes don't move this vote
+        let accuracy: u32 = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "oracle_accuracy"), oracle.clone()))
+            .unwrap_or(100);
+        let posted_stake: i128 = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, POSTED_STAKE_KEY), oracle.clone()))
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &(
+                Symbol::new(&env, VOTE_WEIGHTS_KEY),
+                market_id.clone(),
+                oracle.clone(),
+            ),
+            &(accuracy, posted_stake),
+        );
+
         // 7. Store attestation with timestamp
         let attestation = Attestation {
             attestor: oracle.clone(),
             outcome: attestation_result,
             timestamp: current_time,
+            evidence_uri,
         };
         let attestation_key = (
             Symbol::new(&env, "attestation"),
@@ -395,14 +828,24 @@ impl OracleManager {
         voters.push_back(oracle.clone());
         env.storage().persistent().set(&voters_key, &voters);
 
-        // 9. Update attestation count per outcome
+        // 8b. Track market in the oracle's attestation history
+        let history_key = (Symbol::new(&env, ORACLE_HISTORY_KEY), oracle.clone());
+        let mut history: Vec<BytesN<32>> = env
+            .storage()
+            .persistent()
+            .get(&history_key)
+            .unwrap_or(Vec::new(&env));
+        history.push_back(market_id.clone());
+        env.storage().persistent().set(&history_key, &history);
+
+        // 9. Update binary attestation counts (multi-outcome tallies come from the votes)
         if attestation_result == 1 {
             let yes_count_key = (Symbol::new(&env, ATTEST_COUNT_YES_KEY), market_id.clone());
             let current_count: u32 = env.storage().persistent().get(&yes_count_key).unwrap_or(0);
             env.storage()
                 .persistent()
                 .set(&yes_count_key, &(current_count + 1));
-        } else {
+        } else if attestation_result == 0 {
             let no_count_key = (Symbol::new(&env, ATTEST_COUNT_NO_KEY), market_id.clone());
             let current_count: u32 = env.storage().persistent().get(&no_count_key).unwrap_or(0);
             env.storage()
@@ -412,14 +855,43 @@ impl OracleManager {
 
         // 10. Emit AttestationSubmitted(market_id, attestor, outcome)
         AttestationSubmittedEvent {
-            market_id,
+            market_id: market_id.clone(),
             oracle,
             attestation_result,
         }
         .publish(&env);
+
+        // 10b. Emit running tallies so indexers can track progress without polling
+        let (yes_count, no_count) = Self::get_attestation_counts(env.clone(), market_id.clone());
+        ConsensusProgressEvent {
+            market_id: market_id.clone(),
+            yes_count,
+            no_count,
+            threshold: consensus_threshold(&env, &market_id),
+        }
+        .publish(&env);
+
+        // 11. Persist the consensus outcome the first time it is reached
+        let reached_key = (Symbol::new(&env, "consensus_reached"), market_id.clone());
+        if !env.storage().persistent().has(&reached_key) {
+            let (consensus_reached, outcome) =
+                Self::check_consensus(env.clone(), market_id.clone());
+            if consensus_reached {
+                env.storage().persistent().set(&reached_key, &outcome);
+                ConsensusReachedEvent {
+                    market_id,
+                    outcome,
+                    timestamp: current_time,
+                }
+                .publish(&env);
+            }
+        }
     }
 
     /// Check if consensus has been reached for market
+    ///
+    /// Pure view: never writes storage. The result is persisted by
+    /// submit_attestation when consensus is first reached.
     pub fn check_consensus(env: Env, market_id: BytesN<32>) -> (bool, u32) {
         // 1. Query attestations for market_id
         let voters_key = (Symbol::new(&env, "voters"), market_id.clone());
@@ -429,45 +901,435 @@ impl OracleManager {
             .get(&voters_key)
             .unwrap_or(Vec::new(&env));
 
-        // 2. Get required threshold
-        let threshold: u32 = env
+        // 1b. Reputation- and stake-weighted modes tally weights instead of heads
+        let weight_mode: Symbol = env
             .storage()
             .persistent()
-            .get(&Symbol::new(&env, REQUIRED_CONSENSUS_KEY))
-            .unwrap_or(0);
+            .get(&(Symbol::new(&env, MARKET_WEIGHT_MODE_KEY), market_id.clone()))
+            .unwrap_or(Symbol::new(&env, "count"));
+        if weight_mode != Symbol::new(&env, "count") {
+            return weighted_consensus(&env, &market_id, &voters, &weight_mode);
+        }
+
+        // 2. Get required threshold
+        let threshold = consensus_threshold(&env, &market_id);
 
         if voters.len() < threshold {
             return (false, 0);
         }
 
         // 3. Count votes for each outcome
-        let mut yes_votes = 0;
-        let mut no_votes = 0;
+        let outcome_count = market_outcome_count(&env, &market_id);
+        let mut tallies: Vec<u32> = Vec::new(&env);
+        for _ in 0..outcome_count {
+            tallies.push_back(0);
+        }
 
         for oracle in voters.iter() {
             let vote_key = (Symbol::new(&env, "vote"), market_id.clone(), oracle);
             let vote: u32 = env.storage().persistent().get(&vote_key).unwrap_or(0);
-            if vote == 1 {
-                yes_votes += 1;
-            } else {
-                no_votes += 1;
+            if vote < outcome_count {
+                tallies.set(vote, tallies.get(vote).unwrap_or(0) + 1);
             }
         }
 
-        // 4. Compare counts against threshold
-        // Winner is the one that reached the threshold first
-        // If both reach threshold (possible if threshold is low), we favor the one with more votes
-        // If tied and both >= threshold, return false (no clear winner yet)
-        if yes_votes >= threshold && yes_votes > no_votes {
-            (true, 1)
-        } else if no_votes >= threshold && no_votes > yes_votes {
-            (true, 0)
-        } else if yes_votes >= threshold && no_votes >= threshold && yes_votes == no_votes {
-            // Tie scenario appropriately handled: no consensus if tied but threshold met
-            (false, 0)
-        } else {
-            (false, 0)
+        // 3b. In first-to-threshold mode the first outcome to reach the threshold
+        // wins, even if another outcome later overtakes it
+        let mode: Symbol = env
+            .storage()
+            .persistent()
+            .get(&(
+                Symbol::new(&env, MARKET_CONSENSUS_MODE_KEY),
+                market_id.clone(),
+            ))
+            .unwrap_or(Symbol::new(&env, "leading"));
+        if mode == Symbol::new(&env, "first_to_threshold") {
+            return match first_past_threshold(&env, &market_id, &voters, outcome_count, threshold) {
+                Some(outcome) => (true, outcome),
+                None => (false, 0),
+            };
+        }
+
+        // 4. Compare counts against threshold
+        // The winner must reach the threshold and strictly lead every other outcome;
+        // a tie at the top means no clear winner yet
+        let mut winner = 0u32;
+        let mut top_votes = 0u32;
+        let mut tied = false;
+        for (outcome, votes) in tallies.iter().enumerate() {
+            if votes > top_votes {
+                winner = outcome as u32;
+                top_votes = votes;
+                tied = false;
+            } else if votes == top_votes {
+                tied = true;
+            }
+        }
+
+        if top_votes >= threshold && !tied {
+            return (true, winner);
+        }
+
+        // 5. Optionally break a threshold-tie in favor of the outcome that crossed first
+        let tie_policy: Symbol = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, TIE_POLICY_KEY))
+            .unwrap_or(Symbol::new(&env, "no_consensus"));
+        if tied && top_votes >= threshold && tie_policy == Symbol::new(&env, "earliest") {
+            if let Some(outcome) =
+                earliest_to_threshold(&env, &market_id, &voters, &tallies, top_votes, threshold)
+            {
+                return (true, outcome);
+            }
+        }
+
+        (false, 0)
+    }
+
+    /// Admin: Set the treasury that pays keeper rewards for finalization
+    pub fn set_treasury(env: Env, treasury: Address) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, TREASURY_KEY), &treasury);
+    }
+
+    /// Admin: Select how a tie at the top is resolved in check_consensus.
+    /// "no_consensus" (default) leaves the market unresolved; "earliest" awards
+    /// the outcome whose attestations reached the threshold first.
+    pub fn set_tie_policy(env: Env, policy: Symbol) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        if policy != Symbol::new(&env, "no_consensus") && policy != Symbol::new(&env, "earliest") {
+            panic!("Invalid tie policy");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, TIE_POLICY_KEY), &policy);
+    }
+
+    /// Admin: Set the oracles eligible to attest markets in a category
+    pub fn set_category_oracles(env: Env, category: Symbol, oracles: Vec<Address>) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        env.storage().persistent().set(
+            &(Symbol::new(&env, CATEGORY_ORACLES_KEY), category),
+            &oracles,
+        );
+    }
+
+    /// Admin: Restrict a market to the oracles allowlisted for `category`
+    pub fn set_market_category(env: Env, market_id: BytesN<32>, category: Symbol) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        if !env
+            .storage()
+            .persistent()
+            .has(&(Symbol::new(&env, MARKET_RES_TIME_KEY), market_id.clone()))
+        {
+            panic!("Market not registered");
+        }
+
+        env.storage().persistent().set(
+            &(Symbol::new(&env, MARKET_CATEGORY_KEY), market_id),
+            &category,
+        );
+    }
+
+    /// Admin: Make the consensus threshold a fraction (bps) of currently active
+    /// oracles, so quorum adapts as oracles are deregistered. 0 restores the
+    /// fixed threshold (default). Applies to markets registered afterwards.
+    pub fn set_adaptive_quorum(env: Env, quorum_bps: u32) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        if quorum_bps > 10000 {
+            panic!("Invalid quorum");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, ADAPTIVE_QUORUM_KEY), &quorum_bps);
+    }
+
+    /// Admin: Set how many seconds before resolution_time attestations are
+    /// accepted on markets flagged as early-finalizable
+    pub fn set_attestation_grace(env: Env, grace_seconds: u64) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, ATTEST_GRACE_KEY), &grace_seconds);
+    }
+
+    /// Admin: Flag a market whose data source finalizes before resolution_time
+    pub fn set_early_finalizable(env: Env, market_id: BytesN<32>, enabled: bool) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        if !env
+            .storage()
+            .persistent()
+            .has(&(Symbol::new(&env, MARKET_RES_TIME_KEY), market_id.clone()))
+        {
+            panic!("Market not registered");
+        }
+
+        env.storage().persistent().set(
+            &(Symbol::new(&env, EARLY_FINALIZE_KEY), market_id),
+            &enabled,
+        );
+    }
+
+    /// Admin: Select how check_consensus picks a winner. "leading" (default)
+    /// requires the winner to strictly lead at the threshold; "first_to_threshold"
+    /// awards the first outcome whose attestations reach the threshold.
+    /// Applies to markets registered afterwards; existing markets keep their mode.
+    pub fn set_consensus_mode(env: Env, mode: Symbol) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        if mode != Symbol::new(&env, "leading") && mode != Symbol::new(&env, "first_to_threshold") {
+            panic!("Invalid consensus mode");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, CONSENSUS_MODE_KEY), &mode);
+    }
+
+    /// Admin: Select how votes are weighted in check_consensus.
+    /// "count" (default) gives every oracle one vote against the oracle-count
+    /// threshold; "reputation" weights votes by accuracy score and "stake" by
+    /// posted stake, and the winner then needs `weighted_threshold` total weight.
+    /// Applies to markets registered afterwards; existing markets keep their snapshot.
+    pub fn set_vote_weight_mode(env: Env, mode: Symbol, weighted_threshold: i128) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        if mode != Symbol::new(&env, "count")
+            && mode != Symbol::new(&env, "reputation")
+            && mode != Symbol::new(&env, "stake")
+        {
+            panic!("Invalid vote weight mode");
+        }
+        if mode != Symbol::new(&env, "count") && weighted_threshold <= 0 {
+            panic!("Weighted threshold must be positive");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, VOTE_WEIGHT_MODE_KEY), &mode);
+        env.storage().persistent().set(
+            &Symbol::new(&env, WEIGHTED_THRESHOLD_KEY),
+            &weighted_threshold,
+        );
+    }
+
+    /// Admin: Set the token oracles deposit as voting stake
+    pub fn set_stake_token(env: Env, token: Address) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, STAKE_TOKEN_KEY), &token);
+    }
+
+    /// Deposit stake tokens, used as the oracle's vote weight in stake-weighted
+    /// mode. Kept apart from the slashable challenge stake in `get_oracle_stake`.
+    pub fn deposit_oracle_stake(env: Env, oracle: Address, amount: i128) {
+        oracle.require_auth();
+
+        let oracle_key = (Symbol::new(&env, "oracle"), oracle.clone());
+        let is_registered: bool = env.storage().persistent().get(&oracle_key).unwrap_or(false);
+        if !is_registered {
+            panic!("Oracle not registered");
+        }
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        let stake_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, STAKE_TOKEN_KEY))
+            .expect("Stake token not set");
+        token::Client::new(&env, &stake_token).transfer(
+            &oracle,
+            env.current_contract_address(),
+            &amount,
+        );
+
+        let posted_key = (Symbol::new(&env, POSTED_STAKE_KEY), oracle.clone());
+        let posted: i128 = env.storage().persistent().get(&posted_key).unwrap_or(0);
+        let total_posted = posted + amount;
+        env.storage().persistent().set(&posted_key, &total_posted);
+
+        OracleStakePostedEvent {
+            oracle,
+            amount,
+            total_posted,
+        }
+        .publish(&env);
+    }
+
+    /// Withdraw deposited stake tokens. Votes already cast keep the weight
+    /// snapshotted when they were submitted.
+    pub fn withdraw_oracle_stake(env: Env, oracle: Address, amount: i128) {
+        oracle.require_auth();
+
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        let posted_key = (Symbol::new(&env, POSTED_STAKE_KEY), oracle.clone());
+        let posted: i128 = env.storage().persistent().get(&posted_key).unwrap_or(0);
+        if amount > posted {
+            panic!("Insufficient posted stake");
+        }
+
+        let total_posted = posted - amount;
+        env.storage().persistent().set(&posted_key, &total_posted);
+
+        let stake_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, STAKE_TOKEN_KEY))
+            .expect("Stake token not set");
+        token::Client::new(&env, &stake_token).transfer(
+            &env.current_contract_address(),
+            &oracle,
+            &amount,
+        );
+
+        OracleStakeWithdrawnEvent {
+            oracle,
+            amount,
+            total_posted,
+        }
+        .publish(&env);
+    }
+
+    /// Get the stake tokens an oracle has deposited
+    pub fn get_posted_stake(env: Env, oracle: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, POSTED_STAKE_KEY), oracle))
+            .unwrap_or(0)
+    }
+
+    /// Check consensus for several markets in one call
+    pub fn check_consensus_batch(
+        env: Env,
+        market_ids: Vec<BytesN<32>>,
+    ) -> Vec<(BytesN<32>, bool, u32)> {
+        let mut results = Vec::new(&env);
+        for market_id in market_ids.iter() {
+            let (reached, outcome) = Self::check_consensus(env.clone(), market_id.clone());
+            results.push_back((market_id, reached, outcome));
+        }
+        results
+    }
+
+    /// Admin: Re-evaluate an unfinalized market's consensus under the current
+    /// oracle set and rules, refreshing the result cached by submit_attestation
+    pub fn recompute_consensus(env: Env, market_id: BytesN<32>) -> (bool, u32) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        if !env
+            .storage()
+            .persistent()
+            .has(&(Symbol::new(&env, MARKET_RES_TIME_KEY), market_id.clone()))
+        {
+            panic!("Market not registered");
+        }
+        if Self::is_finalized(env.clone(), market_id.clone()) {
+            panic!("Market already finalized");
+        }
+
+        let (reached, outcome) = Self::check_consensus(env.clone(), market_id.clone());
+        let reached_key = (Symbol::new(&env, "consensus_reached"), market_id.clone());
+        if reached {
+            env.storage().persistent().set(&reached_key, &outcome);
+        } else {
+            env.storage().persistent().remove(&reached_key);
+        }
+
+        ConsensusRecomputedEvent {
+            market_id,
+            reached,
+            outcome,
+            timestamp: env.ledger().timestamp(),
         }
+        .publish(&env);
+
+        (reached, outcome)
+    }
+
+    /// Get the consensus outcome cached when it was first reached, if any
+    pub fn get_cached_consensus(env: Env, market_id: BytesN<32>) -> Option<u32> {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "consensus_reached"), market_id))
+    }
+
+    /// Check whether a market's resolution has been finalized
+    pub fn is_finalized(env: Env, market_id: BytesN<32>) -> bool {
+        let result_key = (Symbol::new(&env, "consensus_result"), market_id);
+        env.storage().persistent().has(&result_key)
     }
 
     /// Get the consensus result for a market
@@ -481,10 +1343,18 @@ impl OracleManager {
 
     /// Finalize market resolution after consensus and dispute period
     ///
-    /// Called after consensus reached and dispute period elapsed.
+    /// Permissionless: any keeper may call once consensus is reached and the
+    /// dispute period has elapsed, and is paid the treasury's keeper reward.
     /// Makes cross-contract call to Market.resolve_market().
     /// Locks in final outcome permanently.
-    pub fn finalize_resolution(env: Env, market_id: BytesN<32>, _market_address: Address) {
+    pub fn finalize_resolution(
+        env: Env,
+        market_id: BytesN<32>,
+        _market_address: Address,
+        keeper: Address,
+    ) {
+        keeper.require_auth();
+
         // 1. Validate market is registered
         let market_key = (Symbol::new(&env, MARKET_RES_TIME_KEY), market_id.clone());
         let resolution_time: u64 = env
@@ -493,7 +1363,11 @@ impl OracleManager {
             .get(&market_key)
             .expect("Market not registered");
 
-        // 2. Validate consensus reached
+        // 2. Validate consensus reached and not already finalized
+        let result_key = (Symbol::new(&env, "consensus_result"), market_id.clone());
+        if env.storage().persistent().has(&result_key) {
+            panic!("Market already finalized");
+        }
         let (consensus_reached, final_outcome) =
             Self::check_consensus(env.clone(), market_id.clone());
         if !consensus_reached {
@@ -508,7 +1382,6 @@ impl OracleManager {
         }
 
         // 4. Store consensus result permanently
-        let result_key = (Symbol::new(&env, "consensus_result"), market_id.clone());
         env.storage().persistent().set(&result_key, &final_outcome);
 
         // 5. Cross-contract call to Market.resolve_market()
@@ -521,11 +1394,28 @@ impl OracleManager {
 
         // 6. Emit ResolutionFinalized event
         ResolutionFinalizedEvent {
-            market_id,
+            market_id: market_id.clone(),
             final_outcome,
             timestamp: current_time,
         }
         .publish(&env);
+
+        // 7. Pay the keeper for timely finalization. Best effort: a misconfigured
+        // or failing treasury must not block settlement.
+        let treasury: Option<Address> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, TREASURY_KEY));
+        if let Some(treasury) = treasury {
+            let paid = env.try_invoke_contract::<i128, soroban_sdk::Error>(
+                &treasury,
+                &Symbol::new(&env, "pay_keeper_reward"),
+                (keeper.clone(),).into_val(&env),
+            );
+            if !matches!(paid, Ok(Ok(_))) {
+                KeeperRewardFailedEvent { market_id, keeper }.publish(&env);
+            }
+        }
     }
 
     /// Challenge an attestation (dispute oracle honesty)
@@ -584,6 +1474,16 @@ impl OracleManager {
         // 6. Store challenge
         env.storage().persistent().set(&challenge_key, &challenge);
 
+        let mut pending: Vec<(BytesN<32>, Address)> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, PENDING_CHALLENGES_KEY))
+            .unwrap_or(Vec::new(&env));
+        pending.push_back((market_id.clone(), oracle.clone()));
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, PENDING_CHALLENGES_KEY), &pending);
+
         // 7. Mark market as having active challenge (pause finalization)
         let market_challenge_key = (Symbol::new(&env, "market_challenged"), market_id.clone());
         env.storage().persistent().set(&market_challenge_key, &true);
@@ -656,7 +1556,9 @@ impl OracleManager {
             let remaining_stake = oracle_stake - slashed_amount;
             env.storage().persistent().set(&stake_key, &remaining_stake);
 
-            // 6c. Reward challenger with slashed amount
+            // 6c. Reward challenger out of the slashed amount, mirroring the
+            // forfeited challenge stake an honest oracle receives below
+            let challenger_reward = slashed_amount.min(CHALLENGE_STAKE_AMOUNT);
             let challenger_reward_key = (
                 Symbol::new(&env, "challenger_reward"),
                 challenge.challenger.clone(),
@@ -666,11 +1568,26 @@ impl OracleManager {
                 .persistent()
                 .get(&challenger_reward_key)
                 .unwrap_or(0);
-            env.storage()
+            env.storage().persistent().set(
+                &challenger_reward_key,
+                &(current_rewards + challenger_reward),
+            );
+
+            // 6d. Route the rest of the slashed stake to the configured destination.
+            // Oracle stake is a ledger balance, so this is a credit, not a transfer.
+            let destination: Symbol = env
+                .storage()
                 .persistent()
-                .set(&challenger_reward_key, &(current_rewards + slashed_amount));
+                .get(&Symbol::new(&env, SLASH_DESTINATION_KEY))
+                .unwrap_or(Symbol::new(&env, "treasury"));
+            let slashed_key = (Symbol::new(&env, SLASHED_FUNDS_KEY), destination);
+            let slashed_total: i128 = env.storage().persistent().get(&slashed_key).unwrap_or(0);
+            env.storage().persistent().set(
+                &slashed_key,
+                &(slashed_total + slashed_amount - challenger_reward),
+            );
 
-            // 6d. If accuracy drops below threshold (50%), deregister oracle
+            // 6e. If accuracy drops below threshold (50%), deregister oracle
             if accuracy < 50 {
                 let oracle_key = (Symbol::new(&env, "oracle"), oracle.clone());
                 env.storage().persistent().set(&oracle_key, &false);
@@ -725,13 +1642,28 @@ impl OracleManager {
         challenge.resolved = true;
         env.storage().persistent().set(&challenge_key, &challenge);
 
-        // 10. Remove market challenge flag (allow finalization)
-        let market_challenge_key = (Symbol::new(&env, "market_challenged"), market_id.clone());
-        env.storage().persistent().remove(&market_challenge_key);
-
-        // 11. Emit ChallengeResolved event
-        ChallengeResolvedEvent {
-            oracle,
+        let pending: Vec<(BytesN<32>, Address)> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, PENDING_CHALLENGES_KEY))
+            .unwrap_or(Vec::new(&env));
+        let mut remaining = Vec::new(&env);
+        for (pending_market, pending_oracle) in pending.iter() {
+            if pending_market != market_id || pending_oracle != oracle {
+                remaining.push_back((pending_market, pending_oracle));
+            }
+        }
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, PENDING_CHALLENGES_KEY), &remaining);
+
+        // 10. Remove market challenge flag (allow finalization)
+        let market_challenge_key = (Symbol::new(&env, "market_challenged"), market_id.clone());
+        env.storage().persistent().remove(&market_challenge_key);
+
+        // 11. Emit ChallengeResolved event
+        ChallengeResolvedEvent {
+            oracle,
             challenger: challenge.challenger,
             challenge_valid,
             new_reputation,
@@ -740,15 +1672,105 @@ impl OracleManager {
         .publish(&env);
     }
 
-    /// Get all attestations for a market
-    ///
-    /// TODO: Get Attestations
-    /// - Query attestations map by market_id
-    /// - Return all oracles' attestations for this market
-    /// - Include: oracle_address, result, data_hash, timestamp
-    /// - Include: consensus status and vote counts
-    pub fn get_attestations(_env: Env, _market_id: BytesN<32>) -> Vec<Symbol> {
-        todo!("See get attestations TODO above")
+    /// Admin: Choose where slashed oracle stake goes: "treasury" (default),
+    /// "burn", or "redistribute" to a pool for honest oracles
+    pub fn set_slash_destination(env: Env, destination: Symbol) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        if destination != Symbol::new(&env, "treasury")
+            && destination != Symbol::new(&env, "burn")
+            && destination != Symbol::new(&env, "redistribute")
+        {
+            panic!("Invalid slash destination");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, SLASH_DESTINATION_KEY), &destination);
+    }
+
+    /// Get total slashed stake credited to a destination (accounting only:
+    /// oracle stake is tracked as a ledger balance, not held as tokens)
+    pub fn get_slashed_funds(env: Env, destination: Symbol) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, SLASHED_FUNDS_KEY), destination))
+            .unwrap_or(0)
+    }
+
+    /// Get rewards credited to a challenger for successful challenges
+    pub fn get_challenger_reward(env: Env, challenger: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "challenger_reward"), challenger))
+            .unwrap_or(0)
+    }
+
+    /// Get all unresolved challenges as (market_id, challenged_oracle) pairs
+    pub fn get_pending_challenges(env: Env) -> Vec<(BytesN<32>, Address)> {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, PENDING_CHALLENGES_KEY))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get all attestations for a market, in submission order
+    pub fn get_attestations(env: Env, market_id: BytesN<32>) -> Vec<Attestation> {
+        let voters_key = (Symbol::new(&env, "voters"), market_id.clone());
+        let voters: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&voters_key)
+            .unwrap_or(Vec::new(&env));
+
+        let mut attestations = Vec::new(&env);
+        for oracle in voters.iter() {
+            let attestation_key = (Symbol::new(&env, "attestation"), market_id.clone(), oracle);
+            if let Some(attestation) = env
+                .storage()
+                .persistent()
+                .get::<_, Attestation>(&attestation_key)
+            {
+                attestations.push_back(attestation);
+            }
+        }
+        attestations
+    }
+
+    /// Get the oracles that attested a market, in submission order
+    pub fn get_voters(env: Env, market_id: BytesN<32>) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "voters"), market_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get every attestation an oracle has submitted, oldest first
+    pub fn get_oracle_attestation_history(env: Env, oracle: Address) -> Vec<Attestation> {
+        let history_key = (Symbol::new(&env, ORACLE_HISTORY_KEY), oracle.clone());
+        let history: Vec<BytesN<32>> = env
+            .storage()
+            .persistent()
+            .get(&history_key)
+            .unwrap_or(Vec::new(&env));
+
+        let mut attestations = Vec::new(&env);
+        for market_id in history.iter() {
+            let attestation_key = (Symbol::new(&env, "attestation"), market_id, oracle.clone());
+            if let Some(attestation) = env
+                .storage()
+                .persistent()
+                .get::<_, Attestation>(&attestation_key)
+            {
+                attestations.push_back(attestation);
+            }
+        }
+        attestations
     }
 
     /// Get oracle info and reputation
@@ -775,15 +1797,58 @@ impl OracleManager {
 
     /// Admin: Update oracle consensus threshold
     ///
-    /// TODO: Set Consensus Threshold
-    /// - Require admin authentication
-    /// - Validate new_threshold > 0 and <= total_oracles
-    /// - Validate reasonable (e.g., 2 of 3, 3 of 5, etc.)
-    /// - Update required_consensus
-    /// - Apply to future markets only
-    /// - Emit ConsensusThresholdUpdated(new_threshold, old_threshold)
-    pub fn set_consensus_threshold(_env: Env, _new_threshold: u32) {
-        todo!("See set consensus threshold TODO above")
+    /// Applies to future markets only: markets keep the threshold snapshotted at
+    /// register_market.
+    pub fn set_consensus_threshold(env: Env, new_threshold: u32) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        let oracle_count: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ORACLE_COUNT_KEY))
+            .unwrap_or(0);
+        if new_threshold == 0 || new_threshold > oracle_count {
+            panic!("Invalid consensus threshold");
+        }
+
+        let old_threshold: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, REQUIRED_CONSENSUS_KEY))
+            .unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, REQUIRED_CONSENSUS_KEY), &new_threshold);
+
+        ConsensusThresholdUpdatedEvent {
+            new_threshold,
+            old_threshold,
+        }
+        .publish(&env);
+    }
+
+    /// Admin: Limit each oracle to `max_attestations` per `window_seconds`
+    pub fn set_attestation_rate_limit(env: Env, max_attestations: u32, window_seconds: u64) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        if max_attestations == 0 || window_seconds == 0 {
+            panic!("Invalid rate limit");
+        }
+
+        env.storage().persistent().set(
+            &Symbol::new(&env, ATTEST_RATE_LIMIT_KEY),
+            &(max_attestations, window_seconds),
+        );
     }
 
     /// Get consensus report
@@ -821,7 +1886,10 @@ impl OracleManager {
     /// Get oracle's accuracy score
     pub fn get_oracle_accuracy(env: Env, oracle: Address) -> u32 {
         let accuracy_key = (Symbol::new(&env, "oracle_accuracy"), oracle);
-        env.storage().persistent().get(&accuracy_key).unwrap_or(0)
+        env.storage()
+            .persistent()
+            .get(&accuracy_key)
+            .expect("oracle not found")
     }
 
     /// Emergency: Override oracle consensus if all oracles compromised
@@ -836,7 +1904,7 @@ impl OracleManager {
     /// Parameters:
     /// - approvers: Vec of admin addresses approving this override
     /// - market_id: Market to override
-    /// - forced_outcome: Outcome to set (0=NO, 1=YES)
+    /// - forced_outcome: Outcome to set, below the market's outcome count (0=NO, 1=YES for binary)
     /// - justification_hash: Hash of justification document (for transparency)
     pub fn emergency_override(
         env: Env,
@@ -845,9 +1913,9 @@ impl OracleManager {
         forced_outcome: u32,
         justification_hash: BytesN<32>,
     ) {
-        // 1. Validate forced_outcome is binary (0 or 1)
-        if forced_outcome > 1 {
-            panic!("Invalid outcome: must be 0 or 1");
+        // 1. Validate forced_outcome is one of the market's outcomes
+        if forced_outcome >= market_outcome_count(&env, &market_id) {
+            panic!("Invalid outcome: out of range for market");
         }
 
         // 2. Get admin signers and required signatures
@@ -890,164 +1958,1304 @@ impl OracleManager {
             valid_approver_count += 1;
         }
 
-        // 5. Ensure no duplicate approvers (each admin can only approve once)
-        if valid_approver_count != approvers.len() {
-            panic!("Duplicate approvers detected");
-        }
+        // 5. Ensure no duplicate approvers (each admin can only approve once)
+        if valid_approver_count != approvers.len() {
+            panic!("Duplicate approvers detected");
+        }
+
+        // 6. Cooldown, forced result, audit record and event
+        apply_override(
+            &env,
+            approvers,
+            market_id,
+            forced_outcome,
+            justification_hash,
+        );
+    }
+
+    /// Admin: Add an address to the multi-sig admin signer set
+    pub fn add_admin_signer(env: Env, signer: Address) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized");
+        admin.require_auth();
+
+        let mut admin_signers: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_SIGNERS_KEY))
+            .unwrap_or(Vec::new(&env));
+        if admin_signers.contains(&signer) {
+            panic!("Signer already added");
+        }
+        admin_signers.push_back(signer);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, ADMIN_SIGNERS_KEY), &admin_signers);
+    }
+
+    /// Propose a two-admin emergency override for a market.
+    /// Replaces any pending proposal for the market; returns the proposal nonce,
+    /// which the confirming admin must echo before the proposal expires.
+    pub fn propose_override(
+        env: Env,
+        proposer: Address,
+        market_id: BytesN<32>,
+        forced_outcome: u32,
+        justification_hash: BytesN<32>,
+    ) -> u64 {
+        proposer.require_auth();
+        require_admin_signer(&env, &proposer);
+
+        if forced_outcome >= market_outcome_count(&env, &market_id) {
+            panic!("Invalid outcome: out of range for market");
+        }
+        let market_key = (Symbol::new(&env, MARKET_RES_TIME_KEY), market_id.clone());
+        if !env.storage().persistent().has(&market_key) {
+            panic!("Market not registered");
+        }
+
+        let nonce: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, OVERRIDE_NONCE_KEY))
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, OVERRIDE_NONCE_KEY), &nonce);
+
+        let proposal = OverrideProposal {
+            nonce,
+            proposer,
+            market_id: market_id.clone(),
+            forced_outcome,
+            justification_hash,
+            expires_at: env.ledger().timestamp() + OVERRIDE_PROPOSAL_TTL,
+        };
+        env.storage().persistent().set(
+            &(Symbol::new(&env, OVERRIDE_PROPOSAL_KEY), market_id),
+            &proposal,
+        );
+
+        nonce
+    }
+
+    /// Confirm a pending override as a second admin. The nonce must match the
+    /// pending proposal exactly and the proposal must not have expired.
+    pub fn confirm_override(env: Env, confirmer: Address, market_id: BytesN<32>, nonce: u64) {
+        confirmer.require_auth();
+        require_admin_signer(&env, &confirmer);
+
+        let proposal_key = (Symbol::new(&env, OVERRIDE_PROPOSAL_KEY), market_id.clone());
+        let proposal: OverrideProposal = env
+            .storage()
+            .persistent()
+            .get(&proposal_key)
+            .expect("No pending override");
+
+        if proposal.nonce != nonce {
+            panic!("Override nonce mismatch");
+        }
+        if env.ledger().timestamp() > proposal.expires_at {
+            panic!("Override proposal expired");
+        }
+        if proposal.proposer == confirmer {
+            panic!("Duplicate approvers detected");
+        }
+
+        env.storage().persistent().remove(&proposal_key);
+
+        let mut approvers = Vec::new(&env);
+        approvers.push_back(proposal.proposer);
+        approvers.push_back(confirmer);
+        apply_override(
+            &env,
+            approvers,
+            market_id,
+            proposal.forced_outcome,
+            proposal.justification_hash,
+        );
+    }
+
+    /// Get the pending override proposal for a market, if any
+    pub fn get_override_proposal(env: Env, market_id: BytesN<32>) -> Option<OverrideProposal> {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, OVERRIDE_PROPOSAL_KEY), market_id))
+    }
+
+    /// Get emergency override record for a market (for audit purposes)
+    pub fn get_override_record(env: Env, market_id: BytesN<32>) -> Option<EmergencyOverrideRecord> {
+        let override_record_key = (Symbol::new(&env, "override_record"), market_id);
+        env.storage().persistent().get(&override_record_key)
+    }
+
+    /// Check if market was manually overridden
+    pub fn is_manual_override(env: Env, market_id: BytesN<32>) -> bool {
+        let override_flag_key = (Symbol::new(&env, "manual_override"), market_id);
+        env.storage()
+            .persistent()
+            .get(&override_flag_key)
+            .unwrap_or(false)
+    }
+
+    /// Get admin signers list
+    pub fn get_admin_signers(env: Env) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_SIGNERS_KEY))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get required signatures for emergency override
+    pub fn get_required_signatures(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, REQUIRED_SIGNATURES_KEY))
+            .unwrap_or(2)
+    }
+
+    /// Get override cooldown period
+    pub fn get_override_cooldown(env: Env) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, OVERRIDE_COOLDOWN_KEY))
+            .unwrap_or(86400)
+    }
+
+    /// Get last override timestamp
+    pub fn get_last_override_time(env: Env) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, LAST_OVERRIDE_TIME_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Get all oracle configuration in a single call
+    pub fn get_config(env: Env) -> OracleConfig {
+        let storage = env.storage().persistent();
+        OracleConfig {
+            admin: storage
+                .get(&Symbol::new(&env, ADMIN_KEY))
+                .expect("Oracle not initialized"),
+            required_consensus: storage
+                .get(&Symbol::new(&env, REQUIRED_CONSENSUS_KEY))
+                .unwrap_or(0),
+            oracle_count: storage
+                .get(&Symbol::new(&env, ORACLE_COUNT_KEY))
+                .unwrap_or(0),
+            required_signatures: storage
+                .get(&Symbol::new(&env, REQUIRED_SIGNATURES_KEY))
+                .unwrap_or(2),
+            override_cooldown: storage
+                .get(&Symbol::new(&env, OVERRIDE_COOLDOWN_KEY))
+                .unwrap_or(86400),
+            tie_policy: storage
+                .get(&Symbol::new(&env, TIE_POLICY_KEY))
+                .unwrap_or(Symbol::new(&env, "no_consensus")),
+            consensus_mode: storage
+                .get(&Symbol::new(&env, CONSENSUS_MODE_KEY))
+                .unwrap_or(Symbol::new(&env, "leading")),
+            vote_weight_mode: storage
+                .get(&Symbol::new(&env, VOTE_WEIGHT_MODE_KEY))
+                .unwrap_or(Symbol::new(&env, "count")),
+            weighted_threshold: storage
+                .get(&Symbol::new(&env, WEIGHTED_THRESHOLD_KEY))
+                .unwrap_or(0),
+            adaptive_quorum: storage
+                .get(&Symbol::new(&env, ADAPTIVE_QUORUM_KEY))
+                .unwrap_or(0),
+            attest_grace: storage
+                .get(&Symbol::new(&env, ATTEST_GRACE_KEY))
+                .unwrap_or(0),
+            slash_destination: storage
+                .get(&Symbol::new(&env, SLASH_DESTINATION_KEY))
+                .unwrap_or(Symbol::new(&env, "treasury")),
+            treasury: storage.get(&Symbol::new(&env, TREASURY_KEY)),
+            stake_token: storage.get(&Symbol::new(&env, STAKE_TOKEN_KEY)),
+        }
+    }
+}
+
+/// Number of valid outcomes for a market (binary unless registered otherwise)
+fn market_outcome_count(env: &Env, market_id: &BytesN<32>) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&(
+            Symbol::new(env, MARKET_OUTCOME_COUNT_KEY),
+            market_id.clone(),
+        ))
+        .unwrap_or(2)
+}
+
+/// Whether a market opted into the pre-resolution attestation grace window
+fn is_early_finalizable(env: &Env, market_id: &BytesN<32>) -> bool {
+    env.storage()
+        .persistent()
+        .get(&(Symbol::new(env, EARLY_FINALIZE_KEY), market_id.clone()))
+        .unwrap_or(false)
+}
+
+/// Votes required for consensus on a market: a fraction of currently active
+/// oracles if the market was registered in adaptive mode, else the threshold
+/// snapshotted at registration, else the global threshold
+fn consensus_threshold(env: &Env, market_id: &BytesN<32>) -> u32 {
+    let quorum_bps: u32 = env
+        .storage()
+        .persistent()
+        .get(&(Symbol::new(env, MARKET_QUORUM_KEY), market_id.clone()))
+        .unwrap_or(0);
+    if quorum_bps > 0 {
+        let active: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, ORACLE_COUNT_KEY))
+            .unwrap_or(0);
+        return (active * quorum_bps).div_ceil(10000).max(1);
+    }
+    let threshold_key = (Symbol::new(env, MARKET_THRESHOLD_KEY), market_id.clone());
+    match env.storage().persistent().get(&threshold_key) {
+        Some(threshold) => threshold,
+        None => env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, REQUIRED_CONSENSUS_KEY))
+            .unwrap_or(0),
+    }
+}
+
+/// Replay votes in submission order and return the first outcome to reach
+/// `threshold`, if any
+fn first_past_threshold(
+    env: &Env,
+    market_id: &BytesN<32>,
+    voters: &Vec<Address>,
+    outcome_count: u32,
+    threshold: u32,
+) -> Option<u32> {
+    let mut running: Vec<u32> = Vec::new(env);
+    for _ in 0..outcome_count {
+        running.push_back(0);
+    }
+
+    for oracle in voters.iter() {
+        let vote: u32 = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(env, "vote"), market_id.clone(), oracle))
+            .unwrap_or(0);
+        if vote >= outcome_count {
+            continue;
+        }
+        let count = running.get(vote).unwrap_or(0) + 1;
+        if count >= threshold.max(1) {
+            return Some(vote);
+        }
+        running.set(vote, count);
+    }
+
+    None
+}
+
+/// Consensus with votes weighted by oracle accuracy ("reputation") or posted
+/// stake ("stake"), both as snapshotted when the vote was cast: the leading
+/// outcome must strictly outweigh every other and reach the weighted threshold.
+/// Tie policy and consensus mode do not apply.
+fn weighted_consensus(
+    env: &Env,
+    market_id: &BytesN<32>,
+    voters: &Vec<Address>,
+    weight_mode: &Symbol,
+) -> (bool, u32) {
+    let threshold: i128 = env
+        .storage()
+        .persistent()
+        .get(&(
+            Symbol::new(env, MARKET_WEIGHT_THRESHOLD_KEY),
+            market_id.clone(),
+        ))
+        .unwrap_or(0);
+    let by_stake = *weight_mode == Symbol::new(env, "stake");
+
+    let outcome_count = market_outcome_count(env, market_id);
+    let mut tallies: Vec<i128> = Vec::new(env);
+    for _ in 0..outcome_count {
+        tallies.push_back(0);
+    }
+    for oracle in voters.iter() {
+        let vote_key = (Symbol::new(env, "vote"), market_id.clone(), oracle.clone());
+        let vote: u32 = env.storage().persistent().get(&vote_key).unwrap_or(0);
+        if vote >= outcome_count {
+            continue;
+        }
+        let (accuracy, posted_stake): (u32, i128) = env
+            .storage()
+            .persistent()
+            .get(&(
+                Symbol::new(env, VOTE_WEIGHTS_KEY),
+                market_id.clone(),
+                oracle,
+            ))
+            .unwrap_or((100, 0));
+        let weight: i128 = if by_stake {
+            posted_stake
+        } else {
+            accuracy as i128
+        };
+        tallies.set(vote, tallies.get(vote).unwrap_or(0) + weight);
+    }
+
+    let mut winner = 0u32;
+    let mut top_weight = 0i128;
+    let mut tied = false;
+    for (outcome, weight) in tallies.iter().enumerate() {
+        if weight > top_weight {
+            winner = outcome as u32;
+            top_weight = weight;
+            tied = false;
+        } else if weight == top_weight {
+            tied = true;
+        }
+    }
+
+    if top_weight >= threshold && top_weight > 0 && !tied {
+        (true, winner)
+    } else {
+        (false, 0)
+    }
+}
+
+/// Among the outcomes tied at `top_votes`, find the one whose threshold-th
+/// attestation has the earliest timestamp. None if two crossed at the same time.
+fn earliest_to_threshold(
+    env: &Env,
+    market_id: &BytesN<32>,
+    voters: &Vec<Address>,
+    tallies: &Vec<u32>,
+    top_votes: u32,
+    threshold: u32,
+) -> Option<u32> {
+    let mut running: Vec<u32> = Vec::new(env);
+    for _ in 0..tallies.len() {
+        running.push_back(0);
+    }
+
+    let mut best: Option<(u32, u64)> = None;
+    let mut ambiguous = false;
+    for oracle in voters.iter() {
+        let attestation: Option<Attestation> = env.storage().persistent().get(&(
+            Symbol::new(env, "attestation"),
+            market_id.clone(),
+            oracle,
+        ));
+        let Some(attestation) = attestation else {
+            continue;
+        };
+        let outcome = attestation.outcome;
+        if tallies.get(outcome).unwrap_or(0) != top_votes {
+            continue;
+        }
+
+        let count = running.get(outcome).unwrap_or(0) + 1;
+        running.set(outcome, count);
+        if count != threshold.max(1) {
+            continue;
+        }
+
+        match best {
+            None => best = Some((outcome, attestation.timestamp)),
+            Some((_, crossed_at)) if attestation.timestamp < crossed_at => {
+                best = Some((outcome, attestation.timestamp));
+                ambiguous = false;
+            }
+            Some((_, crossed_at)) if attestation.timestamp == crossed_at => ambiguous = true,
+            _ => {}
+        }
+    }
+
+    if ambiguous {
+        None
+    } else {
+        best.map(|(outcome, _)| outcome)
+    }
+}
+
+/// Verify that `addr` is in the multi-sig admin signer set
+fn require_admin_signer(env: &Env, addr: &Address) {
+    let admin_signers: Vec<Address> = env
+        .storage()
+        .persistent()
+        .get(&Symbol::new(env, ADMIN_SIGNERS_KEY))
+        .expect("Oracle not initialized");
+    if !admin_signers.contains(addr) {
+        panic!("Invalid approver: not an admin");
+    }
+}
+
+/// Apply an approved emergency override: cooldown check, forced result, audit record and event
+fn apply_override(
+    env: &Env,
+    approvers: Vec<Address>,
+    market_id: BytesN<32>,
+    forced_outcome: u32,
+    justification_hash: BytesN<32>,
+) {
+    // 1. Check cooldown period
+    let last_override_time: u64 = env
+        .storage()
+        .persistent()
+        .get(&Symbol::new(env, LAST_OVERRIDE_TIME_KEY))
+        .unwrap_or(0);
+
+    let cooldown_period: u64 = env
+        .storage()
+        .persistent()
+        .get(&Symbol::new(env, OVERRIDE_COOLDOWN_KEY))
+        .unwrap_or(86400);
+
+    let current_time = env.ledger().timestamp();
+
+    if last_override_time > 0 && (current_time - last_override_time) < cooldown_period {
+        panic!("Cooldown period not elapsed");
+    }
+
+    // 2. Verify market exists
+    let market_key = (Symbol::new(env, MARKET_RES_TIME_KEY), market_id.clone());
+    if !env.storage().persistent().has(&market_key) {
+        panic!("Market not registered");
+    }
+
+    // 3. Store consensus result (override any existing consensus)
+    let result_key = (Symbol::new(env, "consensus_result"), market_id.clone());
+    env.storage().persistent().set(&result_key, &forced_outcome);
+
+    // 4. Mark market as manually overridden for audit purposes
+    let override_flag_key = (Symbol::new(env, "manual_override"), market_id.clone());
+    env.storage().persistent().set(&override_flag_key, &true);
+
+    // 5. Create and store complete override record
+    let override_record = EmergencyOverrideRecord {
+        market_id: market_id.clone(),
+        forced_outcome,
+        justification_hash: justification_hash.clone(),
+        approvers: approvers.clone(),
+        timestamp: current_time,
+    };
+
+    let override_record_key = (Symbol::new(env, "override_record"), market_id.clone());
+    env.storage()
+        .persistent()
+        .set(&override_record_key, &override_record);
+
+    // 6. Update last override timestamp
+    env.storage()
+        .persistent()
+        .set(&Symbol::new(env, LAST_OVERRIDE_TIME_KEY), &current_time);
+
+    // 7. Emit EmergencyOverride event with all details
+    #[contractevent(topics = ["emergency_override_event", "v1"])]
+    pub struct EmergencyOverrideEvent {
+        pub market_id: BytesN<32>,
+        pub forced_outcome: u32,
+        pub justification_hash: BytesN<32>,
+        pub approvers: Vec<Address>,
+        pub timestamp: u64,
+    }
+
+    EmergencyOverrideEvent {
+        market_id,
+        forced_outcome,
+        justification_hash,
+        approvers,
+        timestamp: current_time,
+    }
+    .publish(env);
+}
+
+/// Count an attestation against the oracle's rate-limit window
+fn consume_attestation_quota(env: &Env, oracle: &Address, now: u64) {
+    let limit: Option<(u32, u64)> = env
+        .storage()
+        .persistent()
+        .get(&Symbol::new(env, ATTEST_RATE_LIMIT_KEY));
+    let Some((max_attestations, window)) = limit else {
+        return;
+    };
+
+    let window_key = (Symbol::new(env, ORACLE_RATE_WINDOW_KEY), oracle.clone());
+    let (window_start, count): (u64, u32) = env
+        .storage()
+        .persistent()
+        .get(&window_key)
+        .unwrap_or((now, 0));
+    let (window_start, count) = if now >= window_start + window {
+        (now, 0)
+    } else {
+        (window_start, count)
+    };
+
+    if count >= max_attestations {
+        panic!("oracle rate limited");
+    }
+    env.storage()
+        .persistent()
+        .set(&window_key, &(window_start, count + 1));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Events, Ledger};
+    use soroban_sdk::{token, Address, Env, Map, TryFromVal, Val};
+
+    /// Data of the last event `contract` published with `name` as its first topic
+    fn last_event_data(env: &Env, contract: &Address, name: &str) -> Map<Symbol, Val> {
+        let name = Symbol::new(env, name);
+        env.events()
+            .all()
+            .iter()
+            .filter(|(source, topics, _)| {
+                source == contract
+                    && Symbol::try_from_val(env, &topics.get(0).unwrap()).ok() == Some(name.clone())
+            })
+            .last()
+            .map(|(_, _, data)| data.into_val(env))
+            .expect("Event not published")
+    }
+
+    // Do NOT expose contractimpl or initialize here, only use OracleManagerClient
+    fn setup_oracle(env: &Env) -> (OracleManagerClient<'_>, Address, Address, Address) {
+        let admin = Address::generate(env);
+        let oracle1 = Address::generate(env);
+        let oracle2 = Address::generate(env);
+
+        let oracle_id = env.register(OracleManager, ());
+        let oracle_client = OracleManagerClient::new(env, &oracle_id);
+
+        env.mock_all_auths();
+        oracle_client.initialize(&admin, &2); // Require 2 oracles for consensus
+
+        (oracle_client, admin, oracle1, oracle2)
+    }
+
+    fn register_test_oracles(
+        env: &Env,
+        oracle_client: &OracleManagerClient,
+        oracle1: &Address,
+        oracle2: &Address,
+    ) {
+        oracle_client.register_oracle(oracle1, &Symbol::new(env, "Oracle1"));
+        oracle_client.register_oracle(oracle2, &Symbol::new(env, "Oracle2"));
+    }
+
+    fn create_market_id(env: &Env) -> BytesN<32> {
+        BytesN::from_array(env, &[1u8; 32])
+    }
+
+    #[test]
+    #[should_panic(expected = "oracle rate limited")]
+    fn test_attestation_rate_limit_rejects_excess() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+        oracle_client.set_attestation_rate_limit(&2u32, &3600u64);
+
+        let resolution_time = env.ledger().timestamp() + 100;
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        for i in 0..3u8 {
+            let market_id = BytesN::from_array(&env, &[10 + i; 32]);
+            oracle_client.register_market(&market_id, &resolution_time, &2u32);
+        }
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+
+        // Two attestations fit in the window, the third reverts
+        for i in 0..3u8 {
+            let market_id = BytesN::from_array(&env, &[10 + i; 32]);
+            oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash, &None);
+        }
+    }
+
+    #[test]
+    fn test_attestation_rate_limit_resets_after_window() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+        oracle_client.set_attestation_rate_limit(&1u32, &3600u64);
+
+        let resolution_time = env.ledger().timestamp() + 100;
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        let market_a = BytesN::from_array(&env, &[20u8; 32]);
+        let market_b = BytesN::from_array(&env, &[21u8; 32]);
+        oracle_client.register_market(&market_a, &resolution_time, &2u32);
+        oracle_client.register_market(&market_b, &resolution_time, &2u32);
+
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+        oracle_client.submit_attestation(&oracle1, &market_a, &1, &data_hash, &None);
+
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1 + 3600);
+        oracle_client.submit_attestation(&oracle1, &market_b, &1, &data_hash, &None);
+        assert!(oracle_client.get_attestation(&market_b, &oracle1).is_some());
+    }
+
+    #[test]
+    fn test_check_consensus_batch_matches_individual() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let resolution_time = env.ledger().timestamp() + 100;
+        let settled = BytesN::from_array(&env, &[40u8; 32]);
+        let pending = BytesN::from_array(&env, &[41u8; 32]);
+        oracle_client.register_market(&settled, &resolution_time, &2u32);
+        oracle_client.register_market(&pending, &resolution_time, &2u32);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        oracle_client.submit_attestation(&oracle1, &settled, &1, &data_hash, &None);
+        oracle_client.submit_attestation(&oracle2, &settled, &1, &data_hash, &None);
+        oracle_client.submit_attestation(&oracle1, &pending, &0, &data_hash, &None);
+
+        let mut ids = Vec::new(&env);
+        ids.push_back(settled.clone());
+        ids.push_back(pending.clone());
+        let results = oracle_client.check_consensus_batch(&ids);
+
+        assert_eq!(results.len(), 2);
+        let (settled_reached, settled_outcome) = oracle_client.check_consensus(&settled);
+        let (pending_reached, pending_outcome) = oracle_client.check_consensus(&pending);
+        assert_eq!(
+            results.get(0).unwrap(),
+            (settled.clone(), settled_reached, settled_outcome)
+        );
+        assert_eq!(
+            results.get(1).unwrap(),
+            (pending.clone(), pending_reached, pending_outcome)
+        );
+        assert!(settled_reached);
+        assert!(!pending_reached);
+    }
+
+    #[test]
+    fn test_get_attestation_at_submission_order() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&market_id, &resolution_time, &2u32);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        oracle_client.submit_attestation(&oracle2, &market_id, &0, &data_hash, &None);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash, &None);
+
+        let count = oracle_client.get_attestation_count(&market_id);
+        assert_eq!(count, 2);
+        let expected = [oracle2.clone(), oracle1.clone()];
+        for i in 0..count {
+            let attestation = oracle_client.get_attestation_at(&market_id, &i);
+            assert_eq!(attestation.attestor, expected[i as usize]);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "attestation index out of range")]
+    fn test_get_attestation_at_out_of_range() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, _oracle1, _oracle2) = setup_oracle(&env);
+        oracle_client.get_attestation_at(&create_market_id(&env), &0u32);
+    }
+
+    #[test]
+    fn test_override_proposal_expires_before_confirmation() {
+        let env = Env::default();
+        let (oracle_client, admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+        let admin2 = Address::generate(&env);
+        oracle_client.add_admin_signer(&admin2);
+
+        let market_id = create_market_id(&env);
+        oracle_client.register_market(&market_id, &(env.ledger().timestamp() + 100), &2u32);
+        let justification = BytesN::from_array(&env, &[9u8; 32]);
+
+        let stale_nonce = oracle_client.propose_override(&admin, &market_id, &1u32, &justification);
+        env.ledger()
+            .with_mut(|li| li.timestamp += OVERRIDE_PROPOSAL_TTL + 1);
+        assert!(oracle_client
+            .try_confirm_override(&admin2, &market_id, &stale_nonce)
+            .is_err());
+
+        // A fresh proposal supersedes the stale one; only its nonce confirms
+        let nonce = oracle_client.propose_override(&admin, &market_id, &1u32, &justification);
+        assert_ne!(nonce, stale_nonce);
+        assert!(oracle_client
+            .try_confirm_override(&admin2, &market_id, &stale_nonce)
+            .is_err());
+
+        oracle_client.confirm_override(&admin2, &market_id, &nonce);
+        assert!(oracle_client.is_manual_override(&market_id));
+        assert_eq!(oracle_client.get_consensus_result(&market_id), 1);
+        assert_eq!(oracle_client.get_override_proposal(&market_id), None);
+    }
+
+    #[test]
+    fn test_override_outcome_bounded_by_market_outcome_count() {
+        let env = Env::default();
+        let (oracle_client, admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+        let admin2 = Address::generate(&env);
+        oracle_client.add_admin_signer(&admin2);
+
+        let market_id = create_market_id(&env);
+        oracle_client.register_market(&market_id, &(env.ledger().timestamp() + 100), &3u32);
+        let justification = BytesN::from_array(&env, &[9u8; 32]);
+
+        assert!(oracle_client
+            .try_propose_override(&admin, &market_id, &3u32, &justification)
+            .is_err());
+
+        // The third outcome of a three-way market can be forced
+        let nonce = oracle_client.propose_override(&admin, &market_id, &2u32, &justification);
+        oracle_client.confirm_override(&admin2, &market_id, &nonce);
+        assert_eq!(oracle_client.get_consensus_result(&market_id), 2);
+    }
+
+    #[test]
+    fn test_resolution_countdown_changes_sign() {
+        let env = Env::default();
+        let (oracle_client, _, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 7200;
+        oracle_client.register_market(&market_id, &resolution_time, &2u32);
+
+        assert_eq!(oracle_client.get_resolution_countdown(&market_id), 7200);
+
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 60);
+        assert_eq!(oracle_client.get_resolution_countdown(&market_id), -60);
+    }
+
+    #[test]
+    fn test_multi_outcome_market_consensus() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&market_id, &resolution_time, &3u32);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        assert!(oracle_client
+            .try_submit_attestation(&oracle1, &market_id, &3, &data_hash, &None)
+            .is_err());
+
+        oracle_client.submit_attestation(&oracle1, &market_id, &2, &data_hash, &None);
+        assert_eq!(oracle_client.check_consensus(&market_id), (false, 0));
+        oracle_client.submit_attestation(&oracle2, &market_id, &2, &data_hash, &None);
+        assert_eq!(oracle_client.check_consensus(&market_id), (true, 2));
+    }
+
+    #[test]
+    fn test_earliest_tie_policy_awards_first_to_threshold() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+        let oracle3 = Address::generate(&env);
+        let oracle4 = Address::generate(&env);
+        oracle_client.register_oracle(&oracle3, &Symbol::new(&env, "Oracle3"));
+        oracle_client.register_oracle(&oracle4, &Symbol::new(&env, "Oracle4"));
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&market_id, &resolution_time, &2u32);
+
+        // YES reaches the threshold of 2 before NO does
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        let votes = [
+            (&oracle1, 1u32),
+            (&oracle2, 1),
+            (&oracle3, 0),
+            (&oracle4, 0),
+        ];
+        for (i, (oracle, outcome)) in votes.iter().enumerate() {
+            env.ledger()
+                .with_mut(|li| li.timestamp = resolution_time + 1 + i as u64);
+            oracle_client.submit_attestation(oracle, &market_id, outcome, &data_hash, &None);
+        }
+
+        assert_eq!(oracle_client.check_consensus(&market_id), (false, 0));
+
+        oracle_client.set_tie_policy(&Symbol::new(&env, "earliest"));
+        assert_eq!(oracle_client.check_consensus(&market_id), (true, 1));
+    }
+
+    #[test]
+    fn test_attestation_within_grace_for_early_finalizable_market() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 1000;
+        oracle_client.register_market(&market_id, &resolution_time, &2u32);
+        oracle_client.set_attestation_grace(&300);
+        oracle_client.set_early_finalizable(&market_id, &true);
+
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time - 200);
+        let data_hash = BytesN::from_array(&env, &[3u8; 32]);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash, &None);
+
+        assert_eq!(oracle_client.get_attestation_counts(&market_id), (1, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot attest before resolution time")]
+    fn test_attestation_within_grace_rejected_for_unflagged_market() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 1000;
+        oracle_client.register_market(&market_id, &resolution_time, &2u32);
+        oracle_client.set_attestation_grace(&300);
+
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time - 200);
+        let data_hash = BytesN::from_array(&env, &[3u8; 32]);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "oracle not eligible for category")]
+    fn test_non_allowlisted_oracle_rejected_for_category() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&market_id, &resolution_time, &2u32);
+
+        let sports = Symbol::new(&env, "sports");
+        oracle_client.set_category_oracles(&sports, &Vec::from_array(&env, [oracle1.clone()]));
+        oracle_client.set_market_category(&market_id, &sports);
+
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+        let data_hash = BytesN::from_array(&env, &[4u8; 32]);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash, &None);
+        assert_eq!(oracle_client.get_attestation_count(&market_id), 1);
+
+        oracle_client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash, &None);
+    }
+
+    #[test]
+    fn test_adaptive_quorum_shrinks_with_deregistration() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+        let oracle3 = Address::generate(&env);
+        let oracle4 = Address::generate(&env);
+        oracle_client.register_oracle(&oracle3, &Symbol::new(&env, "Oracle3"));
+        oracle_client.register_oracle(&oracle4, &Symbol::new(&env, "Oracle4"));
+
+        let resolution_time = env.ledger().timestamp() + 100;
+        let fixed_market = BytesN::from_array(&env, &[1u8; 32]);
+        oracle_client.register_market(&fixed_market, &resolution_time, &2u32);
+        oracle_client.set_adaptive_quorum(&7500u32);
+        let adaptive_market = BytesN::from_array(&env, &[2u8; 32]);
+        oracle_client.register_market(&adaptive_market, &resolution_time, &2u32);
+
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+        let data_hash = BytesN::from_array(&env, &[5u8; 32]);
+        for market_id in [&fixed_market, &adaptive_market] {
+            oracle_client.submit_attestation(&oracle1, market_id, &1u32, &data_hash, &None);
+            oracle_client.submit_attestation(&oracle2, market_id, &1u32, &data_hash, &None);
+        }
+
+        // Markets registered before the toggle keep the fixed threshold of 2
+        assert_eq!(oracle_client.check_consensus(&fixed_market), (true, 1));
+
+        // 75% of 4 active oracles requires 3 votes
+        assert_eq!(oracle_client.check_consensus(&adaptive_market), (false, 0));
+
+        // With 2 active oracles, 75% rounds up to 2
+        oracle_client.deregister_oracle(&oracle3);
+        oracle_client.deregister_oracle(&oracle4);
+        assert_eq!(oracle_client.check_consensus(&adaptive_market), (true, 1));
+
+        // Turning adaptive quorum off later does not change registered markets
+        oracle_client.set_adaptive_quorum(&0u32);
+        oracle_client.register_oracle(&Address::generate(&env), &Symbol::new(&env, "Oracle5"));
+        oracle_client.register_oracle(&Address::generate(&env), &Symbol::new(&env, "Oracle6"));
+        assert_eq!(oracle_client.check_consensus(&adaptive_market), (false, 0));
+        assert_eq!(oracle_client.check_consensus(&fixed_market), (true, 1));
+    }
+
+    #[test]
+    fn test_consensus_modes_pick_different_winners() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+        let oracle3 = Address::generate(&env);
+        let oracle4 = Address::generate(&env);
+        let oracle5 = Address::generate(&env);
+        oracle_client.register_oracle(&oracle3, &Symbol::new(&env, "Oracle3"));
+        oracle_client.register_oracle(&oracle4, &Symbol::new(&env, "Oracle4"));
+        oracle_client.register_oracle(&oracle5, &Symbol::new(&env, "Oracle5"));
+
+        // Each market keeps the mode in force when it was registered
+        let leading_market = create_market_id(&env);
+        let first_market = BytesN::from_array(&env, &[2u8; 32]);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&leading_market, &resolution_time, &2u32);
+        oracle_client.set_consensus_mode(&Symbol::new(&env, "first_to_threshold"));
+        oracle_client.register_market(&first_market, &resolution_time, &2u32);
+
+        // YES reaches the threshold of 2 first, then NO overtakes with 3
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+        let data_hash = BytesN::from_array(&env, &[6u8; 32]);
+        let votes = [
+            (&oracle1, 1u32),
+            (&oracle2, 1),
+            (&oracle3, 0),
+            (&oracle4, 0),
+            (&oracle5, 0),
+        ];
+        for market_id in [&leading_market, &first_market] {
+            for (oracle, outcome) in votes.iter() {
+                oracle_client.submit_attestation(oracle, market_id, outcome, &data_hash, &None);
+            }
+        }
+
+        assert_eq!(oracle_client.check_consensus(&leading_market), (true, 0));
+        assert_eq!(oracle_client.check_consensus(&first_market), (true, 1));
+
+        // Switching back is not retroactive either
+        oracle_client.set_consensus_mode(&Symbol::new(&env, "leading"));
+        assert_eq!(oracle_client.check_consensus(&first_market), (true, 1));
+    }
+
+    #[test]
+    fn test_slashed_stake_follows_configured_destination() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&market_id, &resolution_time, &2u32);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+        let data_hash = BytesN::from_array(&env, &[7u8; 32]);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash, &None);
+        oracle_client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash, &None);
+
+        let challenger = Address::generate(&env);
+        let reason = Symbol::new(&env, "fraud");
+        let treasury = Symbol::new(&env, "treasury");
+        let redistribute = Symbol::new(&env, "redistribute");
+
+        // Default destination is the treasury
+        let stake1 = oracle_client.get_oracle_stake(&oracle1);
+        oracle_client.challenge_attestation(&challenger, &oracle1, &market_id, &reason);
+        oracle_client.resolve_challenge(&oracle1, &market_id, &true);
+        assert_eq!(
+            oracle_client.get_slashed_funds(&treasury),
+            stake1 / 2 - CHALLENGE_STAKE_AMOUNT
+        );
+
+        // Redirected slashes land in the redistribution pool
+        oracle_client.set_slash_destination(&redistribute);
+        let stake2 = oracle_client.get_oracle_stake(&oracle2);
+        oracle_client.challenge_attestation(&challenger, &oracle2, &market_id, &reason);
+        oracle_client.resolve_challenge(&oracle2, &market_id, &true);
+        assert_eq!(
+            oracle_client.get_slashed_funds(&redistribute),
+            stake2 / 2 - CHALLENGE_STAKE_AMOUNT
+        );
+        assert_eq!(
+            oracle_client.get_slashed_funds(&treasury),
+            stake1 / 2 - CHALLENGE_STAKE_AMOUNT
+        );
+        assert_eq!(
+            oracle_client.get_challenger_reward(&challenger),
+            CHALLENGE_STAKE_AMOUNT * 2
+        );
+    }
 
-        // 6. Check cooldown period
-        let last_override_time: u64 = env
-            .storage()
-            .persistent()
-            .get(&Symbol::new(&env, LAST_OVERRIDE_TIME_KEY))
-            .unwrap_or(0);
+    #[test]
+    fn test_consensus_progress_event_tracks_counts() {
+        let env = Env::default();
+        env.mock_all_auths();
 
-        let cooldown_period: u64 = env
-            .storage()
-            .persistent()
-            .get(&Symbol::new(&env, OVERRIDE_COOLDOWN_KEY))
-            .unwrap_or(86400);
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
 
-        let current_time = env.ledger().timestamp();
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&market_id, &resolution_time, &2u32);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
 
-        if last_override_time > 0 && (current_time - last_override_time) < cooldown_period {
-            panic!("Cooldown period not elapsed");
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        for (i, (oracle, result)) in [(oracle1, 1u32), (oracle2, 0u32)].into_iter().enumerate() {
+            oracle_client.submit_attestation(&oracle, &market_id, &result, &data_hash, &None);
+            // events().all() only covers the latest invocation, so read it first
+            let submitted_data =
+                last_event_data(&env, &oracle_client.address, "attestation_submitted_event");
+            let progress_data =
+                last_event_data(&env, &oracle_client.address, "consensus_progress_event");
+
+            let (yes_count, no_count) = oracle_client.get_attestation_counts(&market_id);
+            assert_eq!(yes_count + no_count, i as u32 + 1);
+            let submitted: Map<Symbol, Val> = Map::from_array(
+                &env,
+                [
+                    (Symbol::new(&env, "market_id"), market_id.into_val(&env)),
+                    (Symbol::new(&env, "oracle"), oracle.into_val(&env)),
+                    (
+                        Symbol::new(&env, "attestation_result"),
+                        result.into_val(&env),
+                    ),
+                ],
+            );
+            let progress: Map<Symbol, Val> = Map::from_array(
+                &env,
+                [
+                    (Symbol::new(&env, "market_id"), market_id.into_val(&env)),
+                    (Symbol::new(&env, "yes_count"), yes_count.into_val(&env)),
+                    (Symbol::new(&env, "no_count"), no_count.into_val(&env)),
+                    (Symbol::new(&env, "threshold"), 2u32.into_val(&env)),
+                ],
+            );
+            assert_eq!(submitted_data, submitted);
+            assert_eq!(progress_data, progress);
         }
+    }
 
-        // 7. Verify market exists
-        let market_key = (Symbol::new(&env, MARKET_RES_TIME_KEY), market_id.clone());
-        if !env.storage().persistent().has(&market_key) {
-            panic!("Market not registered");
-        }
+    #[test]
+    fn test_recompute_consensus_refreshes_cache_after_deregistration() {
+        let env = Env::default();
+        env.mock_all_auths();
 
-        // 8. Store consensus result (override any existing consensus)
-        let result_key = (Symbol::new(&env, "consensus_result"), market_id.clone());
-        env.storage().persistent().set(&result_key, &forced_outcome);
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+        let oracle3 = Address::generate(&env);
+        oracle_client.register_oracle(&oracle3, &Symbol::new(&env, "Oracle3"));
+        oracle_client.set_adaptive_quorum(&10000u32);
 
-        // 9. Mark market as manually overridden for audit purposes
-        let override_flag_key = (Symbol::new(&env, "manual_override"), market_id.clone());
-        env.storage().persistent().set(&override_flag_key, &true);
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&market_id, &resolution_time, &2u32);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+        let data_hash = BytesN::from_array(&env, &[6u8; 32]);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash, &None);
+        oracle_client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash, &None);
+
+        // All 3 active oracles are required, so nothing is cached yet
+        assert_eq!(oracle_client.get_cached_consensus(&market_id), None);
+
+        // Dropping the silent oracle lowers the quorum to the 2 votes already in
+        oracle_client.deregister_oracle(&oracle3);
+        assert_eq!(oracle_client.get_cached_consensus(&market_id), None);
+        assert_eq!(oracle_client.recompute_consensus(&market_id), (true, 1));
+        assert_eq!(oracle_client.get_cached_consensus(&market_id), Some(1));
+    }
 
-        // 10. Create and store complete override record
-        let override_record = EmergencyOverrideRecord {
-            market_id: market_id.clone(),
-            forced_outcome,
-            justification_hash: justification_hash.clone(),
-            approvers: approvers.clone(),
-            timestamp: current_time,
-        };
+    #[test]
+    fn test_stake_weighted_minority_outvotes_majority() {
+        let env = Env::default();
+        env.mock_all_auths();
 
-        let override_record_key = (Symbol::new(&env, "override_record"), market_id.clone());
-        env.storage()
-            .persistent()
-            .set(&override_record_key, &override_record);
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+        let oracle3 = Address::generate(&env);
+        oracle_client.register_oracle(&oracle3, &Symbol::new(&env, "Oracle3"));
+        let token_admin = Address::generate(&env);
+        let stake_token = env
+            .register_stellar_asset_contract_v2(token_admin)
+            .address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &stake_token);
+        oracle_client.set_stake_token(&stake_token);
+        for (oracle, stake) in [
+            (&oracle1, 50_000i128),
+            (&oracle2, 10_000i128),
+            (&oracle3, 10_000i128),
+        ] {
+            token_admin_client.mint(oracle, &stake);
+            oracle_client.deposit_oracle_stake(oracle, &stake);
+        }
+        assert_eq!(
+            token::Client::new(&env, &stake_token).balance(&oracle_client.address),
+            70_000
+        );
 
-        // 11. Update last override timestamp
-        env.storage()
-            .persistent()
-            .set(&Symbol::new(&env, LAST_OVERRIDE_TIME_KEY), &current_time);
+        // Each market is registered under the weighting in force at the time
+        let resolution_time = env.ledger().timestamp() + 100;
+        let count_market = BytesN::from_array(&env, &[1u8; 32]);
+        oracle_client.register_market(&count_market, &resolution_time, &2u32);
+        oracle_client.set_vote_weight_mode(&Symbol::new(&env, "stake"), &40_000i128);
+        let stake_market = BytesN::from_array(&env, &[2u8; 32]);
+        oracle_client.register_market(&stake_market, &resolution_time, &2u32);
+        oracle_client.set_vote_weight_mode(&Symbol::new(&env, "reputation"), &200i128);
+        let reputation_market = BytesN::from_array(&env, &[3u8; 32]);
+        oracle_client.register_market(&reputation_market, &resolution_time, &2u32);
 
-        // 12. Emit EmergencyOverride event with all details
-        #[contractevent]
-        pub struct EmergencyOverrideEvent {
-            pub market_id: BytesN<32>,
-            pub forced_outcome: u32,
-            pub justification_hash: BytesN<32>,
-            pub approvers: Vec<Address>,
-            pub timestamp: u64,
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+        let data_hash = BytesN::from_array(&env, &[7u8; 32]);
+        for market_id in [&count_market, &stake_market, &reputation_market] {
+            oracle_client.submit_attestation(&oracle1, market_id, &1u32, &data_hash, &None);
+            oracle_client.submit_attestation(&oracle2, market_id, &0u32, &data_hash, &None);
+            oracle_client.submit_attestation(&oracle3, market_id, &0u32, &data_hash, &None);
         }
 
-        EmergencyOverrideEvent {
-            market_id,
-            forced_outcome,
-            justification_hash,
-            approvers,
-            timestamp: current_time,
-        }
-        .publish(&env);
-    }
+        // By head count the two low-stake oracles win
+        assert_eq!(oracle_client.check_consensus(&count_market), (true, 0));
+        // Weighted by stake, 50k YES beats 20k NO
+        assert_eq!(oracle_client.check_consensus(&stake_market), (true, 1));
+        // Equal reputations make it a head count again, against the weight threshold
+        assert_eq!(oracle_client.check_consensus(&reputation_market), (true, 0));
+
+        // Changing the weighting later does not move markets already registered
+        oracle_client.set_vote_weight_mode(&Symbol::new(&env, "count"), &0i128);
+        assert_eq!(oracle_client.check_consensus(&stake_market), (true, 1));
+
+        // Weights were snapshotted at vote time: withdrawing afterwards changes nothing
+        oracle_client.withdraw_oracle_stake(&oracle1, &50_000i128);
+        assert_eq!(oracle_client.get_posted_stake(&oracle1), 0);
+        assert_eq!(
+            token::Client::new(&env, &stake_token).balance(&oracle1),
+            50_000
+        );
+        assert_eq!(oracle_client.check_consensus(&stake_market), (true, 1));
 
-    /// Get emergency override record for a market (for audit purposes)
-    pub fn get_override_record(env: Env, market_id: BytesN<32>) -> Option<EmergencyOverrideRecord> {
-        let override_record_key = (Symbol::new(&env, "override_record"), market_id);
-        env.storage().persistent().get(&override_record_key)
+        // Posted stake is a separate ledger from the slashable challenge stake
+        assert_eq!(
+            oracle_client.get_oracle_stake(&oracle1),
+            CHALLENGE_STAKE_AMOUNT * 10
+        );
     }
 
-    /// Check if market was manually overridden
-    pub fn is_manual_override(env: Env, market_id: BytesN<32>) -> bool {
-        let override_flag_key = (Symbol::new(&env, "manual_override"), market_id);
-        env.storage()
-            .persistent()
-            .get(&override_flag_key)
-            .unwrap_or(false)
-    }
+    #[test]
+    fn test_consensus_threshold_event_uses_versioned_topics() {
+        let env = Env::default();
+        env.mock_all_auths();
 
-    /// Get admin signers list
-    pub fn get_admin_signers(env: Env) -> Vec<Address> {
-        env.storage()
-            .persistent()
-            .get(&Symbol::new(&env, ADMIN_SIGNERS_KEY))
-            .unwrap_or(Vec::new(&env))
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+        oracle_client.set_consensus_threshold(&1);
+
+        let (contract, topics, _) = env.events().all().last().unwrap();
+        let expected: Vec<Val> = Vec::from_array(
+            &env,
+            [
+                Symbol::new(&env, "consensus_threshold_updated").into_val(&env),
+                Symbol::new(&env, "v1").into_val(&env),
+            ],
+        );
+        assert_eq!(contract, oracle_client.address);
+        assert_eq!(topics, expected);
     }
 
-    /// Get required signatures for emergency override
-    pub fn get_required_signatures(env: Env) -> u32 {
-        env.storage()
-            .persistent()
-            .get(&Symbol::new(&env, REQUIRED_SIGNATURES_KEY))
-            .unwrap_or(2)
-    }
+    #[test]
+    fn test_get_oracle_accuracy_new_oracle() {
+        let env = Env::default();
+        env.mock_all_auths();
 
-    /// Get override cooldown period
-    pub fn get_override_cooldown(env: Env) -> u64 {
-        env.storage()
-            .persistent()
-            .get(&Symbol::new(&env, OVERRIDE_COOLDOWN_KEY))
-            .unwrap_or(86400)
-    }
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
 
-    /// Get last override timestamp
-    pub fn get_last_override_time(env: Env) -> u64 {
-        env.storage()
-            .persistent()
-            .get(&Symbol::new(&env, LAST_OVERRIDE_TIME_KEY))
-            .unwrap_or(0)
+        assert_eq!(oracle_client.get_oracle_accuracy(&oracle1), 100);
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use soroban_sdk::testutils::{Address as _, Ledger};
-    use soroban_sdk::{Address, Env};
 
-    // Do NOT expose contractimpl or initialize here, only use OracleManagerClient
-    fn setup_oracle(env: &Env) -> (OracleManagerClient<'_>, Address, Address, Address) {
-        let admin = Address::generate(env);
-        let oracle1 = Address::generate(env);
-        let oracle2 = Address::generate(env);
+    #[test]
+    #[should_panic(expected = "oracle not found")]
+    fn test_get_oracle_accuracy_unregistered() {
+        let env = Env::default();
+        env.mock_all_auths();
 
-        let oracle_id = env.register(OracleManager, ());
-        let oracle_client = OracleManagerClient::new(env, &oracle_id);
+        let (oracle_client, _admin, _oracle1, _oracle2) = setup_oracle(&env);
+        oracle_client.get_oracle_accuracy(&Address::generate(&env));
+    }
 
+    #[test]
+    fn test_oracle_attestation_history() {
+        let env = Env::default();
         env.mock_all_auths();
-        oracle_client.initialize(&admin, &2); // Require 2 oracles for consensus
 
-        (oracle_client, admin, oracle1, oracle2)
-    }
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
 
-    fn register_test_oracles(
-        env: &Env,
-        oracle_client: &OracleManagerClient,
-        oracle1: &Address,
-        oracle2: &Address,
-    ) {
-        oracle_client.register_oracle(oracle1, &Symbol::new(env, "Oracle1"));
-        oracle_client.register_oracle(oracle2, &Symbol::new(env, "Oracle2"));
-    }
+        let resolution_time = env.ledger().timestamp() + 100;
+        let market_a = BytesN::from_array(&env, &[30u8; 32]);
+        let market_b = BytesN::from_array(&env, &[31u8; 32]);
+        oracle_client.register_market(&market_a, &resolution_time, &2u32);
+        oracle_client.register_market(&market_b, &resolution_time, &2u32);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
 
-    fn create_market_id(env: &Env) -> BytesN<32> {
-        BytesN::from_array(env, &[1u8; 32])
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        oracle_client.submit_attestation(&oracle1, &market_a, &1, &data_hash, &None);
+        oracle_client.submit_attestation(&oracle1, &market_b, &0, &data_hash, &None);
+
+        let history = oracle_client.get_oracle_attestation_history(&oracle1);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.get(0).unwrap().outcome, 1);
+        assert_eq!(history.get(1).unwrap().outcome, 0);
+        assert_eq!(
+            history.get(0).unwrap(),
+            oracle_client.get_attestation(&market_a, &oracle1).unwrap()
+        );
+        assert_eq!(
+            oracle_client.get_oracle_attestation_history(&oracle2).len(),
+            0
+        );
     }
 
     #[test]
@@ -1062,7 +3270,7 @@ mod tests {
         let resolution_time = env.ledger().timestamp() + 100;
 
         // Register market
-        oracle_client.register_market(&market_id, &resolution_time);
+        oracle_client.register_market(&market_id, &resolution_time, &2u32);
 
         // Move time forward past resolution
         env.ledger()
@@ -1070,7 +3278,7 @@ mod tests {
 
         // Oracle submits attestation
         let data_hash = BytesN::from_array(&env, &[2u8; 32]);
-        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash, &None);
 
         // Challenger challenges the attestation
         let challenger = Address::generate(&env);
@@ -1123,12 +3331,12 @@ mod tests {
         let market_id = create_market_id(&env);
         let resolution_time = env.ledger().timestamp() + 100;
 
-        oracle_client.register_market(&market_id, &resolution_time);
+        oracle_client.register_market(&market_id, &resolution_time, &2u32);
         env.ledger()
             .with_mut(|li| li.timestamp = resolution_time + 1);
 
         let data_hash = BytesN::from_array(&env, &[2u8; 32]);
-        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash, &None);
 
         let challenger = Address::generate(&env);
         let reason = Symbol::new(&env, "fraud");
@@ -1151,12 +3359,12 @@ mod tests {
         let market_id = create_market_id(&env);
         let resolution_time = env.ledger().timestamp() + 100;
 
-        oracle_client.register_market(&market_id, &resolution_time);
+        oracle_client.register_market(&market_id, &resolution_time, &2u32);
         env.ledger()
             .with_mut(|li| li.timestamp = resolution_time + 1);
 
         let data_hash = BytesN::from_array(&env, &[2u8; 32]);
-        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash, &None);
 
         // Get initial oracle stake and accuracy
         let initial_stake = oracle_client.get_oracle_stake(&oracle1);
@@ -1179,6 +3387,12 @@ mod tests {
         let new_stake = oracle_client.get_oracle_stake(&oracle1);
         assert_eq!(new_stake, initial_stake / 2);
 
+        // Verify challenger was credited out of the slashed stake
+        assert_eq!(
+            oracle_client.get_challenger_reward(&challenger),
+            CHALLENGE_STAKE_AMOUNT
+        );
+
         // Verify oracle's accuracy was reduced (by 20%)
         let new_accuracy = oracle_client.get_oracle_accuracy(&oracle1);
         assert_eq!(new_accuracy, 80);
@@ -1198,12 +3412,12 @@ mod tests {
         let market_id = create_market_id(&env);
         let resolution_time = env.ledger().timestamp() + 100;
 
-        oracle_client.register_market(&market_id, &resolution_time);
+        oracle_client.register_market(&market_id, &resolution_time, &2u32);
         env.ledger()
             .with_mut(|li| li.timestamp = resolution_time + 1);
 
         let data_hash = BytesN::from_array(&env, &[2u8; 32]);
-        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash, &None);
 
         let initial_stake = oracle_client.get_oracle_stake(&oracle1);
         let _initial_accuracy = oracle_client.get_oracle_accuracy(&oracle1);
@@ -1249,12 +3463,12 @@ mod tests {
         let market_id = create_market_id(&env);
         let resolution_time = env.ledger().timestamp() + 100;
 
-        oracle_client.register_market(&market_id, &resolution_time);
+        oracle_client.register_market(&market_id, &resolution_time, &2u32);
         env.ledger()
             .with_mut(|li| li.timestamp = resolution_time + 1);
 
         let data_hash = BytesN::from_array(&env, &[2u8; 32]);
-        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash, &None);
 
         let challenger = Address::generate(&env);
         let reason = Symbol::new(&env, "fraud");
@@ -1305,12 +3519,12 @@ mod tests {
         let market_id = create_market_id(&env);
         let resolution_time = env.ledger().timestamp() + 100;
 
-        oracle_client.register_market(&market_id, &resolution_time);
+        oracle_client.register_market(&market_id, &resolution_time, &2u32);
         env.ledger()
             .with_mut(|li| li.timestamp = resolution_time + 1);
 
         let data_hash = BytesN::from_array(&env, &[2u8; 32]);
-        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash, &None);
 
         let challenger = Address::generate(&env);
         let reason = Symbol::new(&env, "fraud");
@@ -1373,15 +3587,15 @@ mod tests {
         let market_id = create_market_id(&env);
         let resolution_time = env.ledger().timestamp() + 100;
 
-        oracle_client.register_market(&market_id, &resolution_time);
+        oracle_client.register_market(&market_id, &resolution_time, &2u32);
         env.ledger()
             .with_mut(|li| li.timestamp = resolution_time + 1);
 
         let data_hash = BytesN::from_array(&env, &[2u8; 32]);
 
         // Both oracles submit attestations
-        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash);
-        oracle_client.submit_attestation(&oracle2, &market_id, &0, &data_hash);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash, &None);
+        oracle_client.submit_attestation(&oracle2, &market_id, &0, &data_hash, &None);
 
         let challenger = Address::generate(&env);
         let reason = Symbol::new(&env, "fraud");
@@ -1394,4 +3608,195 @@ mod tests {
         assert!(oracle_client.get_challenge(&oracle1, &market_id).is_some());
         assert!(oracle_client.get_challenge(&oracle2, &market_id).is_some());
     }
+
+    #[test]
+    fn test_get_pending_challenges() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+
+        oracle_client.register_market(&market_id, &resolution_time, &2u32);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash, &None);
+        oracle_client.submit_attestation(&oracle2, &market_id, &0, &data_hash, &None);
+
+        let challenger = Address::generate(&env);
+        let reason = Symbol::new(&env, "fraud");
+        oracle_client.challenge_attestation(&challenger, &oracle1, &market_id, &reason);
+        oracle_client.challenge_attestation(&challenger, &oracle2, &market_id, &reason);
+
+        let pending = oracle_client.get_pending_challenges();
+        assert_eq!(pending.len(), 2);
+        assert!(pending.contains((market_id.clone(), oracle1.clone())));
+        assert!(pending.contains((market_id.clone(), oracle2.clone())));
+
+        // Resolving one challenge prunes it from the index
+        oracle_client.resolve_challenge(&oracle1, &market_id, &false);
+
+        let pending = oracle_client.get_pending_challenges();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending.get(0).unwrap(), (market_id, oracle2));
+    }
+
+    #[test]
+    fn test_get_config() {
+        let env = Env::default();
+        let (oracle_client, admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let config = oracle_client.get_config();
+        assert_eq!(config.admin, admin);
+        assert_eq!(config.required_consensus, 2);
+        assert_eq!(config.oracle_count, 2);
+        assert_eq!(config.required_signatures, 2);
+        assert_eq!(config.override_cooldown, 86400);
+        assert_eq!(config.tie_policy, Symbol::new(&env, "no_consensus"));
+        assert_eq!(config.consensus_mode, Symbol::new(&env, "leading"));
+        assert_eq!(config.vote_weight_mode, Symbol::new(&env, "count"));
+        assert_eq!(config.weighted_threshold, 0);
+        assert_eq!(config.adaptive_quorum, 0);
+        assert_eq!(config.attest_grace, 0);
+        assert_eq!(config.slash_destination, Symbol::new(&env, "treasury"));
+        assert_eq!(config.treasury, None);
+        assert_eq!(config.stake_token, None);
+
+        env.mock_all_auths();
+        let treasury = Address::generate(&env);
+        let stake_token = Address::generate(&env);
+        oracle_client.set_tie_policy(&Symbol::new(&env, "earliest"));
+        oracle_client.set_consensus_mode(&Symbol::new(&env, "first_to_threshold"));
+        oracle_client.set_vote_weight_mode(&Symbol::new(&env, "stake"), &500);
+        oracle_client.set_adaptive_quorum(&6000);
+        oracle_client.set_attestation_grace(&3600);
+        oracle_client.set_slash_destination(&Symbol::new(&env, "burn"));
+        oracle_client.set_treasury(&treasury);
+        oracle_client.set_stake_token(&stake_token);
+
+        let config = oracle_client.get_config();
+        assert_eq!(config.tie_policy, Symbol::new(&env, "earliest"));
+        assert_eq!(
+            config.consensus_mode,
+            Symbol::new(&env, "first_to_threshold")
+        );
+        assert_eq!(config.vote_weight_mode, Symbol::new(&env, "stake"));
+        assert_eq!(config.weighted_threshold, 500);
+        assert_eq!(config.adaptive_quorum, 6000);
+        assert_eq!(config.attest_grace, 3600);
+        assert_eq!(config.slash_destination, Symbol::new(&env, "burn"));
+        assert_eq!(config.treasury, Some(treasury));
+        assert_eq!(config.stake_token, Some(stake_token));
+    }
+
+    #[test]
+    fn test_threshold_change_does_not_affect_registered_market() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&market_id, &resolution_time, &2u32);
+
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash, &None);
+
+        // Lower the global threshold mid-vote
+        oracle_client.set_consensus_threshold(&1);
+        assert_eq!(oracle_client.get_config().required_consensus, 1);
+
+        // Market still requires its registered threshold of 2
+        let (reached, _) = oracle_client.check_consensus(&market_id);
+        assert!(!reached);
+
+        oracle_client.submit_attestation(&oracle2, &market_id, &1, &data_hash, &None);
+        let (reached, outcome) = oracle_client.check_consensus(&market_id);
+        assert!(reached);
+        assert_eq!(outcome, 1);
+
+        // Newly registered markets use the new threshold
+        let market_id2 = BytesN::from_array(&env, &[9u8; 32]);
+        oracle_client.register_market(&market_id2, &(resolution_time + 10), &2u32);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 11);
+        oracle_client.submit_attestation(&oracle1, &market_id2, &0, &data_hash, &None);
+        let (reached, outcome) = oracle_client.check_consensus(&market_id2);
+        assert!(reached);
+        assert_eq!(outcome, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Oracle not confirmed for market epoch")]
+    fn test_unconfirmed_oracle_ineligible_after_rotation() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        assert_eq!(oracle_client.rotate_oracle_epoch(), 1);
+        oracle_client.confirm_oracle_epoch(&oracle1);
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&market_id, &resolution_time, &2u32);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+
+        // Re-confirmed oracle can attest
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        oracle_client.submit_attestation(&oracle1, &market_id, &1, &data_hash, &None);
+
+        // Oracle that did not re-confirm is ineligible for the new market
+        oracle_client.submit_attestation(&oracle2, &market_id, &1, &data_hash, &None);
+    }
+
+    #[test]
+    fn test_attestation_evidence_uri() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (oracle_client, _admin, oracle1, oracle2) = setup_oracle(&env);
+        register_test_oracles(&env, &oracle_client, &oracle1, &oracle2);
+
+        let market_id = create_market_id(&env);
+        let resolution_time = env.ledger().timestamp() + 100;
+        oracle_client.register_market(&market_id, &resolution_time, &2u32);
+        env.ledger()
+            .with_mut(|li| li.timestamp = resolution_time + 1);
+
+        let data_hash = BytesN::from_array(&env, &[2u8; 32]);
+        let evidence_uri = Symbol::new(&env, "bafybeigdyrzt5sfp7udm7hu76uh7y26");
+        oracle_client.submit_attestation(
+            &oracle1,
+            &market_id,
+            &1,
+            &data_hash,
+            &Some(evidence_uri.clone()),
+        );
+        oracle_client.submit_attestation(&oracle2, &market_id, &1, &data_hash, &None);
+
+        let attestation = oracle_client.get_attestation(&market_id, &oracle1).unwrap();
+        assert_eq!(attestation.evidence_uri, Some(evidence_uri.clone()));
+
+        let attestations = oracle_client.get_attestations(&market_id);
+        assert_eq!(attestations.len(), 2);
+        assert_eq!(
+            attestations.get(0).unwrap().evidence_uri,
+            Some(evidence_uri)
+        );
+        assert_eq!(attestations.get(1).unwrap().evidence_uri, None);
+    }
 }
@@ -1,16 +1,18 @@
 // contract/src/treasury.rs - Treasury Contract Implementation
 // Handles fee collection and reward distribution
 
-use soroban_sdk::{contract, contractevent, contractimpl, token, Address, Env, Symbol};
+use soroban_sdk::{
+    contract, contractevent, contractimpl, token, Address, BytesN, Env, Symbol, Vec,
+};
 
-#[contractevent]
+#[contractevent(topics = ["treasury_initialized_event", "v1"])]
 pub struct TreasuryInitializedEvent {
     pub admin: Address,
     pub usdc_contract: Address,
     pub factory: Address,
 }
 
-#[contractevent]
+#[contractevent(topics = ["fee_distribution_updated_event", "v1"])]
 pub struct FeeDistributionUpdatedEvent {
     pub platform_fee_pct: u32,
     pub leaderboard_fee_pct: u32,
@@ -18,20 +20,26 @@ pub struct FeeDistributionUpdatedEvent {
     pub timestamp: u64,
 }
 
-#[contractevent]
+#[contractevent(topics = ["fee_collected_event", "v1"])]
 pub struct FeeCollectedEvent {
     pub source: Address,
     pub amount: i128,
     pub timestamp: u64,
 }
 
-#[contractevent]
+#[contractevent(topics = ["creator_rewards_event", "v1"])]
 pub struct CreatorRewardsEvent {
     pub total_amount: i128,
     pub count: u32,
 }
 
-#[contractevent]
+#[contractevent(topics = ["creator_rewards_claimed_event", "v1"])]
+pub struct CreatorRewardsClaimedEvent {
+    pub creator: Address,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["emergency_withdrawal_event", "v1"])]
 pub struct EmergencyWithdrawalEvent {
     pub admin: Address,
     pub recipient: Address,
@@ -39,6 +47,27 @@ pub struct EmergencyWithdrawalEvent {
     pub timestamp: u64,
 }
 
+#[contractevent(topics = ["emergency_withdrawal_proposed", "v1"])]
+pub struct EmergencyWithdrawalProposedEvent {
+    pub admin: Address,
+    pub recipient: Address,
+    pub amount: i128,
+    pub executable_at: u64,
+}
+
+#[contractevent(topics = ["keeper_reward_paid_event", "v1"])]
+pub struct KeeperRewardPaidEvent {
+    pub keeper: Address,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["protocol_liquidity_funded_event", "v1"])]
+pub struct ProtocolLiquidityFundedEvent {
+    pub recipient: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
 // Storage keys
 const ADMIN_KEY: &str = "admin";
 const USDC_KEY: &str = "usdc";
@@ -48,6 +77,18 @@ const LEADERBOARD_FEES_KEY: &str = "leaderboard_fees";
 const CREATOR_FEES_KEY: &str = "creator_fees";
 const TOTAL_FEES_KEY: &str = "total_fees";
 const DISTRIBUTION_KEY: &str = "distribution";
+const CREATOR_REWARD_KEY: &str = "creator_reward";
+const RATIO_BOUNDS_KEY: &str = "ratio_bounds";
+const KEEPER_ORACLE_KEY: &str = "keeper_oracle";
+const KEEPER_REWARD_KEY: &str = "keeper_reward";
+const MARKET_FEES_KEY: &str = "market_fees";
+const PENDING_WITHDRAWAL_KEY: &str = "pending_withdrawal";
+
+/// Delay between proposing and executing an emergency withdrawal (48 hours)
+pub const EMERGENCY_WITHDRAW_TIMELOCK: u64 = 172_800;
+
+/// Maximum number of entries accepted by deposit_fees_batch
+pub const MAX_FEE_BATCH_ENTRIES: u32 = 50;
 
 /// Fee distribution ratios (sum to 100)
 #[soroban_sdk::contracttype]
@@ -58,6 +99,26 @@ pub struct FeeRatios {
     pub creator: u32,
 }
 
+/// Aggregate view of all fee pool balances
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TreasuryPools {
+    pub platform: i128,
+    pub leaderboard: i128,
+    pub creator: i128,
+    pub total: i128,
+}
+
+/// Aggregate view of Treasury configuration
+#[soroban_sdk::contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TreasuryConfig {
+    pub admin: Address,
+    pub usdc: Address,
+    pub factory: Address,
+    pub distribution: FeeRatios,
+}
+
 /// TREASURY - Manages fees and reward distribution
 #[contract]
 pub struct Treasury;
@@ -155,6 +216,23 @@ impl Treasury {
             creator: creator_fee_pct,
         };
 
+        // Enforce governance bounds per category, if configured
+        let bounds: Option<(FeeRatios, FeeRatios)> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, RATIO_BOUNDS_KEY));
+        if let Some((min, max)) = bounds {
+            if platform_fee_pct < min.platform
+                || platform_fee_pct > max.platform
+                || leaderboard_fee_pct < min.leaderboard
+                || leaderboard_fee_pct > max.leaderboard
+                || creator_fee_pct < min.creator
+                || creator_fee_pct > max.creator
+            {
+                panic!("Ratio outside bounds");
+            }
+        }
+
         env.storage()
             .persistent()
             .set(&Symbol::new(&env, DISTRIBUTION_KEY), &new_ratios);
@@ -169,14 +247,44 @@ impl Treasury {
         .publish(&env);
     }
 
+    /// Admin: Set per-category min/max percentages enforced by set_fee_distribution
+    pub fn set_ratio_bounds(env: Env, min: FeeRatios, max: FeeRatios) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Not initialized");
+        admin.require_auth();
+
+        if min.platform > max.platform
+            || min.leaderboard > max.leaderboard
+            || min.creator > max.creator
+            || max.platform > 100
+            || max.leaderboard > 100
+            || max.creator > 100
+        {
+            panic!("Invalid ratio bounds");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, RATIO_BOUNDS_KEY), &(min, max));
+    }
+
     /// Deposit fees into treasury and split across pools
-    pub fn deposit_fees(env: Env, source: Address, amount: i128) {
+    ///
+    /// Fees tagged with a `market_id` are also credited to that market's
+    /// cumulative fee counter.
+    pub fn deposit_fees(env: Env, source: Address, amount: i128, market_id: Option<BytesN<32>>) {
         source.require_auth();
         // Validate amount > 0
         if amount <= 0 {
             panic!("Amount must be positive");
         }
 
+        // Split by current ratios
+        let (platform_share, leaderboard_share, creator_share) = self::split_fee(&env, amount);
+
         // Get USDC token contract
         let usdc_token: Address = env
             .storage()
@@ -190,24 +298,17 @@ impl Treasury {
         // The source must have authorized the treasury to pull funds
         token_client.transfer(&source, &treasury_address, &amount);
 
-        // Get current ratios
-        let ratios: FeeRatios = env
-            .storage()
-            .persistent()
-            .get(&Symbol::new(&env, DISTRIBUTION_KEY))
-            .expect("Ratios not set");
-
-        // Calculate shares
-        let platform_share = (amount * ratios.platform as i128) / 100;
-        let leaderboard_share = (amount * ratios.leaderboard as i128) / 100;
-        let creator_share = amount - platform_share - leaderboard_share; // Remainder to creator to avoid rounding dust
-
         // Update pools
         self::update_pool_balance(&env, PLATFORM_FEES_KEY, platform_share);
         self::update_pool_balance(&env, LEADERBOARD_FEES_KEY, leaderboard_share);
         self::update_pool_balance(&env, CREATOR_FEES_KEY, creator_share);
         self::update_pool_balance(&env, TOTAL_FEES_KEY, amount);
 
+        // Attribute the fees to their market
+        if let Some(market_id) = market_id {
+            self::credit_market_fees(&env, market_id, amount);
+        }
+
         // Emit FeeCollected(source, amount, timestamp)
         FeeCollectedEvent {
             source,
@@ -217,6 +318,132 @@ impl Treasury {
         .publish(&env);
     }
 
+    /// Deposit fees for several markets with a single token transfer
+    ///
+    /// Each entry is split by the current ratios and credited to its market,
+    /// exactly as if deposit_fees had been called once per entry.
+    pub fn deposit_fees_batch(env: Env, source: Address, entries: Vec<(BytesN<32>, i128)>) {
+        source.require_auth();
+        if entries.is_empty() {
+            panic!("No fee entries");
+        }
+        if entries.len() > MAX_FEE_BATCH_ENTRIES {
+            panic!("Too many fee entries");
+        }
+
+        let mut total: i128 = 0;
+        for (_, amount) in entries.iter() {
+            if amount <= 0 {
+                panic!("Amount must be positive");
+            }
+            total = total.checked_add(amount).expect("fee amount too large");
+        }
+
+        // Pull the whole batch in one transfer
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("USDC not set");
+        let token_client = token::Client::new(&env, &usdc_token);
+        let treasury_address = env.current_contract_address();
+        token_client.transfer(&source, &treasury_address, &total);
+
+        for (market_id, amount) in entries.iter() {
+            let (platform_share, leaderboard_share, creator_share) = self::split_fee(&env, amount);
+            self::update_pool_balance(&env, PLATFORM_FEES_KEY, platform_share);
+            self::update_pool_balance(&env, LEADERBOARD_FEES_KEY, leaderboard_share);
+            self::update_pool_balance(&env, CREATOR_FEES_KEY, creator_share);
+            self::credit_market_fees(&env, market_id, amount);
+        }
+        self::update_pool_balance(&env, TOTAL_FEES_KEY, total);
+
+        FeeCollectedEvent {
+            source,
+            amount: total,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+    }
+
+    /// Get cumulative fees deposited for a market (0 if none were tagged with it)
+    pub fn get_market_fees(env: Env, market_id: BytesN<32>) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, MARKET_FEES_KEY), market_id))
+            .unwrap_or(0)
+    }
+
+    /// Preview how a deposit of `amount` would be split as (platform, leaderboard, creator)
+    pub fn preview_fee_split(env: Env, amount: i128) -> (i128, i128, i128) {
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        self::split_fee(&env, amount)
+    }
+
+    /// Deposit a trade-fee slice attributed to a specific market creator
+    pub fn deposit_creator_fee(env: Env, source: Address, creator: Address, amount: i128) {
+        source.require_auth();
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("USDC not set");
+        let token_client = token::Client::new(&env, &usdc_token);
+        let treasury_address = env.current_contract_address();
+        token_client.transfer(&source, &treasury_address, &amount);
+
+        let reward_key = (Symbol::new(&env, CREATOR_REWARD_KEY), creator);
+        let current: i128 = env.storage().persistent().get(&reward_key).unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&reward_key, &(current + amount));
+
+        FeeCollectedEvent {
+            source,
+            amount,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+    }
+
+    /// Get a creator's claimable trade-fee rewards
+    pub fn get_creator_rewards(env: Env, creator: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, CREATOR_REWARD_KEY), creator))
+            .unwrap_or(0)
+    }
+
+    /// Creator claims all accrued trade-fee rewards
+    pub fn claim_creator_rewards(env: Env, creator: Address) -> i128 {
+        creator.require_auth();
+
+        let reward_key = (Symbol::new(&env, CREATOR_REWARD_KEY), creator.clone());
+        let amount: i128 = env.storage().persistent().get(&reward_key).unwrap_or(0);
+        if amount <= 0 {
+            panic!("No rewards to claim");
+        }
+        env.storage().persistent().set(&reward_key, &0i128);
+
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("USDC not set");
+        let token_client = token::Client::new(&env, &usdc_token);
+        token_client.transfer(&env.current_contract_address(), &creator, &amount);
+
+        CreatorRewardsClaimedEvent { creator, amount }.publish(&env);
+
+        amount
+    }
+
     /// Get platform fees collected
     pub fn get_platform_fees(env: Env) -> i128 {
         env.storage()
@@ -249,6 +476,35 @@ impl Treasury {
             .unwrap_or(0)
     }
 
+    /// Get all fee pool balances in a single call
+    pub fn get_pools(env: Env) -> TreasuryPools {
+        TreasuryPools {
+            platform: Self::get_platform_fees(env.clone()),
+            leaderboard: Self::get_leaderboard_fees(env.clone()),
+            creator: Self::get_creator_fees(env.clone()),
+            total: Self::get_total_fees(env),
+        }
+    }
+
+    /// Get all treasury configuration in a single call
+    pub fn get_config(env: Env) -> TreasuryConfig {
+        let storage = env.storage().persistent();
+        TreasuryConfig {
+            admin: storage
+                .get(&Symbol::new(&env, ADMIN_KEY))
+                .expect("Not initialized"),
+            usdc: storage
+                .get(&Symbol::new(&env, USDC_KEY))
+                .expect("USDC not set"),
+            factory: storage
+                .get(&Symbol::new(&env, FACTORY_KEY))
+                .expect("Factory not set"),
+            distribution: storage
+                .get(&Symbol::new(&env, DISTRIBUTION_KEY))
+                .expect("Ratios not set"),
+        }
+    }
+
     /// Distribute rewards to leaderboard winners
     pub fn distribute_leaderboard_rewards(_env: Env) {
         todo!("Leaderboard distribution logic not yet implemented")
@@ -305,6 +561,7 @@ impl Treasury {
         env.storage()
             .persistent()
             .set(&Symbol::new(&env, CREATOR_FEES_KEY), &new_balance);
+        self::update_pool_balance(&env, TOTAL_FEES_KEY, -total_amount);
 
         CreatorRewardsEvent {
             total_amount,
@@ -313,6 +570,98 @@ impl Treasury {
         .publish(&env);
     }
 
+    /// Admin: Release platform fees to seed protocol-owned AMM liquidity
+    pub fn fund_protocol_liquidity(env: Env, recipient: Address, amount: i128) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Not initialized");
+        admin.require_auth();
+
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        let platform_fees = Self::get_platform_fees(env.clone());
+        if platform_fees < amount {
+            panic!("Insufficient platform fees");
+        }
+        self::update_pool_balance(&env, PLATFORM_FEES_KEY, -amount);
+        self::update_pool_balance(&env, TOTAL_FEES_KEY, -amount);
+
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("USDC not set");
+        let token_client = token::Client::new(&env, &usdc_token);
+        token_client.transfer(&env.current_contract_address(), &recipient, &amount);
+
+        ProtocolLiquidityFundedEvent {
+            recipient,
+            amount,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+    }
+
+    /// Admin: Set the oracle allowed to request keeper rewards and the reward per finalization
+    pub fn set_keeper_reward(env: Env, oracle: Address, amount: i128) {
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Not initialized");
+        admin.require_auth();
+
+        if amount < 0 {
+            panic!("Amount must be non-negative");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, KEEPER_ORACLE_KEY), &oracle);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, KEEPER_REWARD_KEY), &amount);
+    }
+
+    /// Oracle-only: Pay the keeper reward out of platform fees (capped at what is available)
+    pub fn pay_keeper_reward(env: Env, keeper: Address) -> i128 {
+        let oracle: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, KEEPER_ORACLE_KEY))
+            .expect("Keeper oracle not set");
+        oracle.require_auth();
+
+        let reward: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, KEEPER_REWARD_KEY))
+            .unwrap_or(0);
+        let amount = reward.min(Self::get_platform_fees(env.clone()));
+        if amount <= 0 {
+            return 0;
+        }
+
+        self::update_pool_balance(&env, PLATFORM_FEES_KEY, -amount);
+        self::update_pool_balance(&env, TOTAL_FEES_KEY, -amount);
+
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("USDC not set");
+        let token_client = token::Client::new(&env, &usdc_token);
+        token_client.transfer(&env.current_contract_address(), &keeper, &amount);
+
+        KeeperRewardPaidEvent { keeper, amount }.publish(&env);
+
+        amount
+    }
+
     /// Get treasury balance (total USDC held)
     pub fn get_treasury_balance(env: Env) -> i128 {
         let usdc_token: Address = env
@@ -324,18 +673,67 @@ impl Treasury {
         token_client.balance(&env.current_contract_address())
     }
 
-    /// Emergency withdrawal of funds
-    pub fn emergency_withdraw(env: Env, admin: Address, recipient: Address, amount: i128) {
-        admin.require_auth();
-        let stored_admin: Address = env
+    /// Admin: Verify fee accounting. Returns false if the pools don't sum to
+    /// total_fees or if total_fees exceeds the USDC actually held.
+    pub fn reconcile(env: Env) -> bool {
+        let admin: Address = env
             .storage()
             .persistent()
             .get(&Symbol::new(&env, ADMIN_KEY))
             .expect("Not initialized");
-        if admin != stored_admin {
-            panic!("Unauthorized");
+        admin.require_auth();
+
+        let pools = Self::get_pools(env.clone());
+        let pools_sum = pools.platform + pools.leaderboard + pools.creator;
+        if pools_sum != pools.total {
+            return false;
+        }
+
+        pools.total <= Self::get_treasury_balance(env)
+    }
+
+    /// Emergency withdrawal of funds
+    ///
+    /// Schedules the withdrawal; it can be executed with
+    /// `execute_emergency_withdraw` once EMERGENCY_WITHDRAW_TIMELOCK has passed.
+    /// A new proposal replaces any pending one.
+    pub fn emergency_withdraw(env: Env, admin: Address, recipient: Address, amount: i128) {
+        require_admin(&env, &admin);
+        if amount <= 0 {
+            panic!("Amount must be positive");
         }
 
+        let executable_at = env.ledger().timestamp() + EMERGENCY_WITHDRAW_TIMELOCK;
+        env.storage().persistent().set(
+            &Symbol::new(&env, PENDING_WITHDRAWAL_KEY),
+            &(recipient.clone(), amount, executable_at),
+        );
+
+        EmergencyWithdrawalProposedEvent {
+            admin,
+            recipient,
+            amount,
+            executable_at,
+        }
+        .publish(&env);
+    }
+
+    /// Execute the pending emergency withdrawal after its timelock
+    pub fn execute_emergency_withdraw(env: Env, admin: Address) {
+        require_admin(&env, &admin);
+
+        let (recipient, amount, executable_at): (Address, i128, u64) = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, PENDING_WITHDRAWAL_KEY))
+            .expect("No pending withdrawal");
+        if env.ledger().timestamp() < executable_at {
+            panic!("Withdrawal timelocked");
+        }
+        env.storage()
+            .persistent()
+            .remove(&Symbol::new(&env, PENDING_WITHDRAWAL_KEY));
+
         let usdc_token: Address = env
             .storage()
             .persistent()
@@ -352,6 +750,79 @@ impl Treasury {
         }
         .publish(&env);
     }
+
+    /// Cancel the pending emergency withdrawal
+    pub fn cancel_emergency_withdraw(env: Env, admin: Address) {
+        require_admin(&env, &admin);
+
+        let pending_key = Symbol::new(&env, PENDING_WITHDRAWAL_KEY);
+        if !env.storage().persistent().has(&pending_key) {
+            panic!("No pending withdrawal");
+        }
+        env.storage().persistent().remove(&pending_key);
+    }
+
+    /// Get the pending emergency withdrawal as (recipient, amount, executable_at)
+    pub fn get_pending_withdrawal(env: Env) -> Option<(Address, i128, u64)> {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, PENDING_WITHDRAWAL_KEY))
+    }
+}
+
+/// Require `admin` to be the stored admin and to have authorized the call
+fn require_admin(env: &Env, admin: &Address) {
+    admin.require_auth();
+    let stored_admin: Address = env
+        .storage()
+        .persistent()
+        .get(&Symbol::new(env, ADMIN_KEY))
+        .expect("Not initialized");
+    if *admin != stored_admin {
+        panic!("Unauthorized");
+    }
+}
+
+/// Split a fee by the stored ratios; the creator gets the remainder to avoid rounding dust
+fn split_fee(env: &Env, amount: i128) -> (i128, i128, i128) {
+    let ratios: FeeRatios = env
+        .storage()
+        .persistent()
+        .get(&Symbol::new(env, DISTRIBUTION_KEY))
+        .expect("Ratios not set");
+
+    // Checked to reject amounts that would overflow
+    let platform_share = amount
+        .checked_mul(ratios.platform as i128)
+        .expect("fee amount too large")
+        / 100;
+    let leaderboard_share = amount
+        .checked_mul(ratios.leaderboard as i128)
+        .expect("fee amount too large")
+        / 100;
+    let creator_share = amount - platform_share - leaderboard_share;
+    assert!(
+        platform_share + leaderboard_share + creator_share == amount,
+        "fee split mismatch"
+    );
+
+    (platform_share, leaderboard_share, creator_share)
+}
+
+/// Add `amount` to a market's cumulative fee counter
+fn credit_market_fees(env: &Env, market_id: BytesN<32>, amount: i128) {
+    let market_fees_key = (Symbol::new(env, MARKET_FEES_KEY), market_id);
+    let market_fees: i128 = env
+        .storage()
+        .persistent()
+        .get(&market_fees_key)
+        .unwrap_or(0);
+    let new_market_fees = market_fees
+        .checked_add(amount)
+        .expect("market fees overflow");
+    env.storage()
+        .persistent()
+        .set(&market_fees_key, &new_market_fees);
 }
 
 fn update_pool_balance(env: &Env, key: &str, delta: i128) {
@@ -368,7 +839,7 @@ fn update_pool_balance(env: &Env, key: &str, delta: i128) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::testutils::Address as _;
+    use soroban_sdk::testutils::{Address as _, Ledger};
     use soroban_sdk::{token, Address, Env};
 
     fn create_token_contract<'a>(env: &Env, admin: &Address) -> token::StellarAssetClient<'a> {
@@ -419,4 +890,247 @@ mod tests {
         let (treasury, _, _, _, _) = setup_treasury(&env);
         treasury.set_fee_distribution(&50, &50, &10); // 110%
     }
+
+    #[test]
+    fn test_get_config() {
+        let env = Env::default();
+        let (treasury, usdc, admin, _, factory) = setup_treasury(&env);
+
+        let config = treasury.get_config();
+        assert_eq!(config.admin, admin);
+        assert_eq!(config.usdc, usdc.address);
+        assert_eq!(config.factory, factory);
+        assert_eq!(
+            config.distribution,
+            FeeRatios {
+                platform: 50,
+                leaderboard: 30,
+                creator: 20,
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "fee amount too large")]
+    fn test_deposit_fees_overflow_panics() {
+        let env = Env::default();
+        let (treasury, usdc, _, _, _) = setup_treasury(&env);
+
+        let source = Address::generate(&env);
+        let amount = i128::MAX - 1;
+        usdc.mint(&source, &amount);
+
+        treasury.deposit_fees(&source, &amount, &None);
+    }
+
+    #[test]
+    fn test_get_pools_matches_individual_getters() {
+        let env = Env::default();
+        let (treasury, usdc, _, _, _) = setup_treasury(&env);
+
+        let source = Address::generate(&env);
+        usdc.mint(&source, &1_000);
+        treasury.deposit_fees(&source, &1_000, &None);
+
+        let pools = treasury.get_pools();
+        assert_eq!(pools.platform, treasury.get_platform_fees());
+        assert_eq!(pools.leaderboard, treasury.get_leaderboard_fees());
+        assert_eq!(pools.creator, treasury.get_creator_fees());
+        assert_eq!(pools.total, treasury.get_total_fees());
+        assert_eq!(
+            pools,
+            TreasuryPools {
+                platform: 500,
+                leaderboard: 300,
+                creator: 200,
+                total: 1_000,
+            }
+        );
+    }
+
+    #[test]
+    fn test_preview_fee_split_matches_deposit() {
+        let env = Env::default();
+        let (treasury, usdc, _, _, _) = setup_treasury(&env);
+        treasury.set_fee_distribution(&33, &33, &34);
+
+        let amount = 1_001i128;
+        let (platform, leaderboard, creator) = treasury.preview_fee_split(&amount);
+        assert_eq!(platform + leaderboard + creator, amount);
+
+        // Preview does not touch the pools
+        assert_eq!(treasury.get_total_fees(), 0);
+
+        let source = Address::generate(&env);
+        usdc.mint(&source, &amount);
+        treasury.deposit_fees(&source, &amount, &None);
+
+        let pools = treasury.get_pools();
+        assert_eq!(pools.platform, platform);
+        assert_eq!(pools.leaderboard, leaderboard);
+        assert_eq!(pools.creator, creator);
+        assert_eq!(pools.total, amount);
+    }
+
+    #[test]
+    fn test_deposit_fees_batch_credits_each_market() {
+        let env = Env::default();
+        let (treasury, usdc, _, _, _) = setup_treasury(&env);
+        treasury.set_fee_distribution(&50, &30, &20);
+
+        let market_a = BytesN::from_array(&env, &[1u8; 32]);
+        let market_b = BytesN::from_array(&env, &[2u8; 32]);
+        let market_c = BytesN::from_array(&env, &[3u8; 32]);
+        let mut entries = Vec::new(&env);
+        entries.push_back((market_a.clone(), 1_000i128));
+        entries.push_back((market_b.clone(), 2_000i128));
+        entries.push_back((market_c.clone(), 3_000i128));
+
+        let source = Address::generate(&env);
+        usdc.mint(&source, &6_000i128);
+        treasury.deposit_fees_batch(&source, &entries);
+
+        let pools = treasury.get_pools();
+        assert_eq!(pools.platform, 3_000);
+        assert_eq!(pools.leaderboard, 1_800);
+        assert_eq!(pools.creator, 1_200);
+        assert_eq!(pools.total, 6_000);
+
+        assert_eq!(treasury.get_market_fees(&market_a), 1_000);
+        assert_eq!(treasury.get_market_fees(&market_b), 2_000);
+        assert_eq!(treasury.get_market_fees(&market_c), 3_000);
+        assert_eq!(
+            token::Client::new(&env, &usdc.address).balance(&treasury.address),
+            6_000
+        );
+    }
+
+    #[test]
+    fn test_deposit_fees_batch_rejects_oversized_batch() {
+        let env = Env::default();
+        let (treasury, usdc, _, _, _) = setup_treasury(&env);
+
+        let mut entries = Vec::new(&env);
+        for i in 0..=MAX_FEE_BATCH_ENTRIES {
+            entries.push_back((BytesN::from_array(&env, &[i as u8; 32]), 1i128));
+        }
+        let source = Address::generate(&env);
+        usdc.mint(&source, &(entries.len() as i128));
+        assert!(treasury.try_deposit_fees_batch(&source, &entries).is_err());
+
+        entries.pop_back();
+        treasury.deposit_fees_batch(&source, &entries);
+        assert_eq!(treasury.get_pools().total, MAX_FEE_BATCH_ENTRIES as i128);
+    }
+
+    #[test]
+    fn test_reconcile_detects_emergency_withdraw_drift() {
+        let env = Env::default();
+        let (treasury, usdc, admin, _, _) = setup_treasury(&env);
+
+        let source = Address::generate(&env);
+        usdc.mint(&source, &1_000);
+        treasury.deposit_fees(&source, &1_000, &None);
+        assert!(treasury.reconcile());
+
+        // Pulling funds without adjusting the pools leaves them under-collateralized
+        treasury.emergency_withdraw(&admin, &Address::generate(&env), &600);
+        env.ledger()
+            .with_mut(|li| li.timestamp += EMERGENCY_WITHDRAW_TIMELOCK);
+        treasury.execute_emergency_withdraw(&admin);
+        assert!(!treasury.reconcile());
+    }
+
+    #[test]
+    fn test_emergency_withdraw_waits_for_timelock() {
+        let env = Env::default();
+        let (treasury, usdc, admin, _, _) = setup_treasury(&env);
+
+        let source = Address::generate(&env);
+        usdc.mint(&source, &1_000);
+        treasury.deposit_fees(&source, &1_000, &None);
+
+        // A cancelled proposal can never be executed
+        let recipient = Address::generate(&env);
+        treasury.emergency_withdraw(&admin, &recipient, &400);
+        treasury.cancel_emergency_withdraw(&admin);
+        env.ledger()
+            .with_mut(|li| li.timestamp += EMERGENCY_WITHDRAW_TIMELOCK);
+        assert!(treasury.try_execute_emergency_withdraw(&admin).is_err());
+
+        treasury.emergency_withdraw(&admin, &recipient, &400);
+        assert!(treasury.try_execute_emergency_withdraw(&admin).is_err());
+        env.ledger()
+            .with_mut(|li| li.timestamp += EMERGENCY_WITHDRAW_TIMELOCK);
+        treasury.execute_emergency_withdraw(&admin);
+        assert_eq!(
+            token::Client::new(&env, &usdc.address).balance(&recipient),
+            400
+        );
+    }
+
+    #[test]
+    fn test_pending_withdrawal_visible_until_cancel_or_execute() {
+        let env = Env::default();
+        let (treasury, usdc, admin, _, _) = setup_treasury(&env);
+
+        let source = Address::generate(&env);
+        usdc.mint(&source, &1_000);
+        treasury.deposit_fees(&source, &1_000, &None);
+        assert_eq!(treasury.get_pending_withdrawal(), None);
+
+        let recipient = Address::generate(&env);
+        let executable_at = env.ledger().timestamp() + EMERGENCY_WITHDRAW_TIMELOCK;
+        treasury.emergency_withdraw(&admin, &recipient, &400);
+        assert_eq!(
+            treasury.get_pending_withdrawal(),
+            Some((recipient.clone(), 400, executable_at))
+        );
+
+        treasury.cancel_emergency_withdraw(&admin);
+        assert_eq!(treasury.get_pending_withdrawal(), None);
+
+        treasury.emergency_withdraw(&admin, &recipient, &400);
+        assert!(treasury.try_execute_emergency_withdraw(&admin).is_err());
+        env.ledger()
+            .with_mut(|li| li.timestamp += EMERGENCY_WITHDRAW_TIMELOCK);
+        treasury.execute_emergency_withdraw(&admin);
+        assert_eq!(treasury.get_pending_withdrawal(), None);
+        assert_eq!(
+            token::Client::new(&env, &usdc.address).balance(&recipient),
+            400
+        );
+    }
+
+    #[test]
+    fn test_fee_distribution_respects_ratio_bounds() {
+        let env = Env::default();
+        let (treasury, _, _, _, _) = setup_treasury(&env);
+
+        treasury.set_ratio_bounds(
+            &FeeRatios {
+                platform: 20,
+                leaderboard: 10,
+                creator: 10,
+            },
+            &FeeRatios {
+                platform: 60,
+                leaderboard: 50,
+                creator: 50,
+            },
+        );
+
+        // Platform above its 60% cap is rejected
+        assert!(treasury.try_set_fee_distribution(&98, &1, &1).is_err());
+
+        treasury.set_fee_distribution(&40, &30, &30);
+        assert_eq!(
+            treasury.get_config().distribution,
+            FeeRatios {
+                platform: 40,
+                leaderboard: 30,
+                creator: 30,
+            }
+        );
+    }
 }
@@ -253,10 +253,15 @@ fn test_update_treasury_address() {
 }
 */
 
-use soroban_sdk::{testutils::Address as _, Address, Env, Symbol};
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::{Address as _, Ledger},
+    token, Address, Bytes, BytesN, Env, String, Symbol,
+};
 
 // Import the Factory contract
-use boxmeout::factory::{MarketFactory, MarketFactoryClient};
+use boxmeout::factory::{MarketFactory, MarketFactoryClient, DEFAULT_MAX_MARKET_DURATION};
+use boxmeout::treasury::{Treasury, TreasuryClient};
 // Helper function to create test environment
 fn create_test_env() -> Env {
     Env::default()
@@ -268,12 +273,35 @@ fn register_factory(env: &Env) -> Address {
 }
 
 // Helper to create a mock USDC token
-#[allow(dead_code)]
 fn create_mock_token(env: &Env, admin: &Address) -> Address {
     let token_address = env.register_stellar_asset_contract_v2(admin.clone());
     token_address.address()
 }
 
+// Minimal 6-decimal token: reports decimals and accepts transfers
+#[contract]
+pub struct SixDecimalToken;
+
+#[contractimpl]
+impl SixDecimalToken {
+    pub fn decimals(_env: Env) -> u32 {
+        6
+    }
+
+    pub fn transfer(_env: Env, _from: Address, _to: Address, _amount: i128) {}
+}
+
+// Token whose decimals overflow an i128 fee amount
+#[contract]
+pub struct HugeDecimalToken;
+
+#[contractimpl]
+impl HugeDecimalToken {
+    pub fn decimals(_env: Env) -> u32 {
+        39
+    }
+}
+
 #[test]
 fn test_factory_initialize() {
     let env = create_test_env();
@@ -282,7 +310,7 @@ fn test_factory_initialize() {
 
     // Create mock addresses
     let admin = Address::generate(&env);
-    let usdc = Address::generate(&env);
+    let usdc = create_mock_token(&env, &Address::generate(&env));
     let treasury = Address::generate(&env);
 
     // Call initialize
@@ -294,6 +322,47 @@ fn test_factory_initialize() {
     assert_eq!(market_count, 0);
 }
 
+#[test]
+fn test_factory_get_config() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &Address::generate(&env));
+    let treasury = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin, &usdc, &treasury);
+
+    let config = client.get_config();
+    assert_eq!(config.admin, admin);
+    assert_eq!(config.usdc, usdc);
+    assert_eq!(config.treasury, treasury);
+    assert_eq!(config.market_count, 0);
+    assert_eq!(config.oracle, None);
+    assert_eq!(config.amm, None);
+    assert_eq!(config.decimals, 7);
+    assert_eq!(config.max_market_duration, DEFAULT_MAX_MARKET_DURATION);
+    assert_eq!(config.creation_discount_step_bps, 0);
+    assert_eq!(config.creation_discount_floor_bps, 10000);
+
+    // Later settings are reflected too
+    let oracle = Address::generate(&env);
+    let amm = Address::generate(&env);
+    client.set_oracle(&oracle);
+    client.set_amm(&amm);
+    client.set_max_market_duration(&86_400u64);
+    client.set_creation_discount(&500u32, &5000u32);
+
+    let config = client.get_config();
+    assert_eq!(config.oracle, Some(oracle));
+    assert_eq!(config.amm, Some(amm));
+    assert_eq!(config.max_market_duration, 86_400);
+    assert_eq!(config.creation_discount_step_bps, 500);
+    assert_eq!(config.creation_discount_floor_bps, 5000);
+}
+
 #[test]
 #[should_panic(expected = "already initialized")]
 fn test_factory_initialize_twice_fails() {
@@ -302,7 +371,7 @@ fn test_factory_initialize_twice_fails() {
     let client = MarketFactoryClient::new(&env, &factory_id);
 
     let admin = Address::generate(&env);
-    let usdc = Address::generate(&env);
+    let usdc = create_mock_token(&env, &Address::generate(&env));
     let treasury = Address::generate(&env);
 
     // First initialization
@@ -322,7 +391,7 @@ fn test_create_market_invalid_timestamps() {
 
     // Initialize factory
     let admin = Address::generate(&env);
-    let usdc = Address::generate(&env);
+    let usdc = create_mock_token(&env, &Address::generate(&env));
     let treasury = Address::generate(&env);
     env.mock_all_auths();
     client.initialize(&admin, &usdc, &treasury);
@@ -330,8 +399,9 @@ fn test_create_market_invalid_timestamps() {
     // Try to create market with closing_time > resolution_time
     let creator = Address::generate(&env);
     let title = Symbol::new(&env, "Mayweather");
-    let description = Symbol::new(&env, "MayweatherWins");
+    let description = String::from_str(&env, "MayweatherWins");
     let category = Symbol::new(&env, "Boxing");
+    let criteria = String::from_str(&env, "YesIfMayweatherWinsByKO");
     let closing_time = env.ledger().timestamp() + 86400;
     let resolution_time = closing_time - 3600; // INVALID: before closing time
 
@@ -340,6 +410,7 @@ fn test_create_market_invalid_timestamps() {
         &title,
         &description,
         &category,
+        &criteria,
         &closing_time,
         &resolution_time,
     );
@@ -354,7 +425,7 @@ fn test_create_market_closing_time_in_past() {
 
     // Initialize factory
     let admin = Address::generate(&env);
-    let usdc = Address::generate(&env);
+    let usdc = create_mock_token(&env, &Address::generate(&env));
     let treasury = Address::generate(&env);
     env.mock_all_auths();
     client.initialize(&admin, &usdc, &treasury);
@@ -362,8 +433,9 @@ fn test_create_market_closing_time_in_past() {
     // Try to create market with closing_time in the past
     let creator = Address::generate(&env);
     let title = Symbol::new(&env, "Mayweather");
-    let description = Symbol::new(&env, "MayweatherWins");
+    let description = String::from_str(&env, "MayweatherWins");
     let category = Symbol::new(&env, "Boxing");
+    let criteria = String::from_str(&env, "YesIfMayweatherWinsByKO");
     let closing_time = env.ledger().timestamp() - 100; // In the past
     let resolution_time = closing_time + 3600;
 
@@ -372,15 +444,346 @@ fn test_create_market_closing_time_in_past() {
         &title,
         &description,
         &category,
+        &criteria,
+        &closing_time,
+        &resolution_time,
+    );
+}
+
+#[test]
+fn test_create_market_fee_failure_leaves_no_record() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &Address::generate(&env));
+    let treasury_id = env.register(Treasury, ());
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+
+    env.mock_all_auths();
+    client.initialize(&admin, &usdc, &treasury_id);
+    treasury_client.initialize(&admin, &usdc, &factory_id);
+
+    // Creator holds no USDC, so the creation fee charge fails
+    let creator = Address::generate(&env);
+    let closing_time = env.ledger().timestamp() + 86400;
+    let resolution_time = closing_time + 3600;
+    let result = client.try_create_market(
+        &creator,
+        &Symbol::new(&env, "Mayweather"),
+        &String::from_str(&env, "MayweatherWins"),
+        &Symbol::new(&env, "Boxing"),
+        &String::from_str(&env, "YesIfMayweatherWinsByKO"),
         &closing_time,
         &resolution_time,
     );
+    assert!(result.is_err());
+
+    // No counter increment and no metadata for the would-be market id
+    assert_eq!(client.get_market_count(), 0);
+    let mut hash_input = Bytes::new(&env);
+    hash_input.extend_from_array(&0u32.to_be_bytes());
+    hash_input.extend_from_array(&env.ledger().timestamp().to_be_bytes());
+    let market_id = BytesN::from_array(&env, &env.crypto().sha256(&hash_input).to_array());
+    env.as_contract(&factory_id, || {
+        let metadata_key = (Symbol::new(&env, "market_meta"), market_id.clone());
+        let market_key = (Symbol::new(&env, "market"), market_id.clone());
+        assert!(!env.storage().persistent().has(&metadata_key));
+        assert!(!env.storage().persistent().has(&market_key));
+    });
+}
+
+#[test]
+fn test_create_market_fee_uses_token_decimals() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let admin = Address::generate(&env);
+    let usdc = env.register(SixDecimalToken, ());
+    let treasury_id = env.register(Treasury, ());
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+
+    env.mock_all_auths();
+    client.initialize(&admin, &usdc, &treasury_id);
+    treasury_client.initialize(&admin, &usdc, &factory_id);
+
+    let closing_time = env.ledger().timestamp() + 86400;
+    client.create_market(
+        &Address::generate(&env),
+        &Symbol::new(&env, "Mayweather"),
+        &String::from_str(&env, "MayweatherWins"),
+        &Symbol::new(&env, "Boxing"),
+        &String::from_str(&env, "YesIfMayweatherWinsByKO"),
+        &closing_time,
+        &(closing_time + 3600),
+    );
+
+    // 1 whole unit of a 6-decimal token, not 10^7
+    assert_eq!(treasury_client.get_total_fees(), 1_000_000);
+}
+
+#[test]
+#[should_panic(expected = "token decimals too large")]
+fn test_initialize_rejects_overflowing_token_decimals() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let usdc = env.register(HugeDecimalToken, ());
+    env.mock_all_auths();
+    client.initialize(&Address::generate(&env), &usdc, &Address::generate(&env));
+}
+
+#[test]
+fn test_create_market_duration_limit() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &Address::generate(&env));
+    let treasury_id = env.register(Treasury, ());
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+
+    env.mock_all_auths();
+    client.initialize(&admin, &usdc, &treasury_id);
+    treasury_client.initialize(&admin, &usdc, &factory_id);
+    client.set_max_market_duration(&(30 * 86400u64));
+
+    let creator = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc).mint(&creator, &10_000_000);
+    let now = env.ledger().timestamp();
+
+    // Far-future resolution is rejected
+    let result = client.try_create_market(
+        &creator,
+        &Symbol::new(&env, "Mayweather"),
+        &String::from_str(&env, "MayweatherWins"),
+        &Symbol::new(&env, "Boxing"),
+        &String::from_str(&env, "YesIfMayweatherWinsByKO"),
+        &(now + 86400),
+        &(now + 31 * 86400),
+    );
+    assert!(result.is_err());
+    assert_eq!(client.get_market_count(), 0);
+
+    // Within the limit succeeds
+    client.create_market(
+        &creator,
+        &Symbol::new(&env, "Mayweather"),
+        &String::from_str(&env, "MayweatherWins"),
+        &Symbol::new(&env, "Boxing"),
+        &String::from_str(&env, "YesIfMayweatherWinsByKO"),
+        &(now + 86400),
+        &(now + 30 * 86400),
+    );
+    assert_eq!(client.get_market_count(), 1);
 }
 
 #[test]
 fn test_get_market_by_id() {
-    // TODO: Implement when get_market is ready
-    // Test retrieving market metadata by market_id
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &Address::generate(&env));
+    let treasury_id = env.register(Treasury, ());
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+
+    env.mock_all_auths();
+    client.initialize(&admin, &usdc, &treasury_id);
+    treasury_client.initialize(&admin, &usdc, &factory_id);
+
+    let creator = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc).mint(&creator, &10_000_000);
+
+    let criteria = String::from_str(&env, "YesIfMayweatherWinsByKO");
+    let closing_time = env.ledger().timestamp() + 86400;
+    let resolution_time = closing_time + 3600;
+    let market_id = client.create_market(
+        &creator,
+        &Symbol::new(&env, "Mayweather"),
+        &String::from_str(&env, "MayweatherWins"),
+        &Symbol::new(&env, "Boxing"),
+        &criteria,
+        &closing_time,
+        &resolution_time,
+    );
+
+    let info = client.get_market_info(&market_id);
+    assert_eq!(info.market_id, market_id);
+    assert_eq!(info.creator, creator);
+    assert_eq!(info.resolution_criteria, criteria);
+    assert_eq!(info.closing_time, closing_time);
+    assert_eq!(info.resolution_time, resolution_time);
+}
+
+#[test]
+fn test_creator_market_count() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &Address::generate(&env));
+    let treasury_id = env.register(Treasury, ());
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+
+    env.mock_all_auths();
+    client.initialize(&admin, &usdc, &treasury_id);
+    treasury_client.initialize(&admin, &usdc, &factory_id);
+
+    let creator = Address::generate(&env);
+    let other = Address::generate(&env);
+    let usdc_admin = token::StellarAssetClient::new(&env, &usdc);
+    usdc_admin.mint(&creator, &100_000_000);
+    usdc_admin.mint(&other, &100_000_000);
+
+    let closing_time = env.ledger().timestamp() + 86400;
+    let resolution_time = closing_time + 3600;
+    for author in [&creator, &creator, &creator, &other] {
+        client.create_market(
+            author,
+            &Symbol::new(&env, "Mayweather"),
+            &String::from_str(&env, "MayweatherWins"),
+            &Symbol::new(&env, "Boxing"),
+            &String::from_str(&env, "YesIfMayweatherWinsByKO"),
+            &closing_time,
+            &resolution_time,
+        );
+    }
+
+    assert_eq!(client.get_creator_market_count(&creator), 3);
+    assert_eq!(client.get_creator_markets(&creator).len(), 3);
+    assert_eq!(client.get_creator_market_count(&other), 1);
+    assert_eq!(client.get_creator_market_count(&admin), 0);
+}
+
+#[test]
+fn test_get_active_markets_clamps_limit() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &Address::generate(&env));
+    let treasury_id = env.register(Treasury, ());
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+
+    env.mock_all_auths();
+    client.initialize(&admin, &usdc, &treasury_id);
+    treasury_client.initialize(&admin, &usdc, &factory_id);
+
+    let creator = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc).mint(&creator, &100_000_000);
+
+    let now = env.ledger().timestamp();
+    for closing_time in [now + 100, now + 86400, now + 86400] {
+        client.create_market(
+            &creator,
+            &Symbol::new(&env, "Mayweather"),
+            &String::from_str(&env, "MayweatherWins"),
+            &Symbol::new(&env, "Boxing"),
+            &String::from_str(&env, "YesIfMayweatherWinsByKO"),
+            &closing_time,
+            &(closing_time + 3600),
+        );
+    }
+
+    // A huge limit is clamped to the cap; all three markets fit under it
+    let page = client.get_active_markets(&0u32, &10_000u32);
+    assert_eq!(page.len(), 3);
+    assert!(page.len() <= boxmeout::MAX_PAGE_LIMIT);
+
+    // Once the first market closes it drops out of the listing
+    env.ledger().with_mut(|li| li.timestamp = now + 101);
+    assert_eq!(client.get_active_markets(&0u32, &10_000u32).len(), 2);
+    assert_eq!(client.get_active_markets(&2u32, &10_000u32).len(), 1);
+}
+
+#[test]
+fn test_get_active_markets_pages_past_the_cap() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &Address::generate(&env));
+    let treasury_id = env.register(Treasury, ());
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+
+    env.mock_all_auths();
+    client.initialize(&admin, &usdc, &treasury_id);
+    treasury_client.initialize(&admin, &usdc, &factory_id);
+
+    let creator = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc).mint(&creator, &10_000_000_000);
+
+    let closing_time = env.ledger().timestamp() + 86400;
+    let total = boxmeout::MAX_PAGE_LIMIT + 5;
+    for _ in 0..total {
+        client.create_market(
+            &creator,
+            &Symbol::new(&env, "Mayweather"),
+            &String::from_str(&env, "MayweatherWins"),
+            &Symbol::new(&env, "Boxing"),
+            &String::from_str(&env, "YesIfMayweatherWinsByKO"),
+            &closing_time,
+            &(closing_time + 3600),
+        );
+    }
+    assert_eq!(client.get_market_count(), total);
+
+    // The first page stops at the cap; the next offset picks up the rest
+    let first = client.get_active_markets(&0u32, &10_000u32);
+    assert_eq!(first.len(), boxmeout::MAX_PAGE_LIMIT);
+    let second = client.get_active_markets(&boxmeout::MAX_PAGE_LIMIT, &10_000u32);
+    assert_eq!(second.len(), 5);
+    assert!(!first.contains(second.get(0).unwrap()));
+    assert_eq!(client.get_active_markets(&total, &10_000u32).len(), 0);
+}
+
+#[test]
+fn test_long_description_round_trips() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &Address::generate(&env));
+    let treasury_id = env.register(Treasury, ());
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+
+    env.mock_all_auths();
+    client.initialize(&admin, &usdc, &treasury_id);
+    treasury_client.initialize(&admin, &usdc, &factory_id);
+
+    let creator = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc).mint(&creator, &10_000_000);
+
+    // 200 characters, with spaces and punctuation a Symbol could not hold
+    let text = "Will Floyd Mayweather Jr. win the exhibition bout by knockout? ".repeat(4);
+    let text = &text[..200];
+    assert_eq!(text.len(), 200);
+    let description = String::from_str(&env, text);
+
+    let closing_time = env.ledger().timestamp() + 86400;
+    let market_id = client.create_market(
+        &creator,
+        &Symbol::new(&env, "Mayweather"),
+        &description,
+        &Symbol::new(&env, "Boxing"),
+        &String::from_str(&env, "Resolves YES if Mayweather wins by KO or TKO."),
+        &closing_time,
+        &(closing_time + 3600),
+    );
+
+    let info = client.get_market_info(&market_id);
+    assert_eq!(info.description, description);
+    assert_eq!(info.description.len(), 200);
 }
 
 #[test]
@@ -12,11 +12,15 @@ use boxmeout::{AMMClient, MarketFactory, MarketFactoryClient, OracleManager, Ora
 // ...rest of the file...
 */
 
-use soroban_sdk::{testutils::Address as _, Address, Env, Symbol};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, String, Symbol,
+};
 
 use boxmeout::{
     amm::{AMMClient, AMM},
     factory::{MarketFactory, MarketFactoryClient},
+    market::{PredictionMarket, PredictionMarketClient},
     oracle::{OracleManager, OracleManagerClient},
     treasury::{Treasury, TreasuryClient},
 };
@@ -40,7 +44,9 @@ fn test_complete_prediction_flow() {
 
     // Create addresses
     let admin = Address::generate(&env);
-    let usdc_token = Address::generate(&env);
+    let usdc_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
     let _creator = Address::generate(&env);
     let _user1 = Address::generate(&env);
     let _user2 = Address::generate(&env);
@@ -137,7 +143,9 @@ fn test_market_creation_and_trading() {
     let amm_client = AMMClient::new(&env, &amm_id);
 
     let admin = Address::generate(&env);
-    let usdc_token = Address::generate(&env);
+    let usdc_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
     let treasury = Address::generate(&env);
 
     // Initialize
@@ -153,6 +161,1232 @@ fn test_market_creation_and_trading() {
     // Verify price impact
 }
 
+/// Integration test: Protocol-owned liquidity seeded from the treasury
+#[test]
+fn test_bootstrap_pool_from_treasury() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let factory_id = env.register(MarketFactory, ());
+    let treasury_id = env.register(Treasury, ());
+    let amm_id = env.register(AMM, ());
+
+    let factory_client = MarketFactoryClient::new(&env, &factory_id);
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+    let amm_client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let usdc_admin = Address::generate(&env);
+    let usdc_token = env.register_stellar_asset_contract_v2(usdc_admin).address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc_token);
+
+    factory_client.initialize(&admin, &usdc_token, &treasury_id);
+    treasury_client.initialize(&admin, &usdc_token, &factory_id);
+    amm_client.initialize(&admin, &factory_id, &usdc_token, &100_000_000_000u128);
+
+    // Fund the treasury's platform pool (50% of deposited fees)
+    let fee_source = Address::generate(&env);
+    usdc_client.mint(&fee_source, &10_000_000);
+    treasury_client.deposit_fees(&fee_source, &10_000_000, &None);
+    assert_eq!(treasury_client.get_platform_fees(), 5_000_000);
+
+    // Seed a new pool from the treasury
+    let market_id = BytesN::from_array(&env, &[3u8; 32]);
+    amm_client.bootstrap_pool(&market_id, &2_000_000u128);
+
+    // Protocol holds the LP position
+    assert_eq!(amm_client.get_protocol_lp_tokens(&market_id), 2_000_000);
+    let (yes_reserve, no_reserve, total, _, _) = amm_client.get_pool_state(&market_id);
+    assert_eq!(yes_reserve, 1_000_000);
+    assert_eq!(no_reserve, 1_000_000);
+    assert_eq!(total, 2_000_000);
+    assert_eq!(treasury_client.get_platform_fees(), 3_000_000);
+    assert_eq!(treasury_client.get_treasury_balance(), 8_000_000);
+
+    // Part of the protocol position can be withdrawn back to the treasury
+    amm_client.withdraw_protocol_liquidity(&market_id, &500_000u128);
+    assert_eq!(amm_client.get_protocol_lp_tokens(&market_id), 1_500_000);
+    assert_eq!(treasury_client.get_treasury_balance(), 8_500_000);
+}
+
+/// Integration test: Sell fees are routed to the treasury like buy fees
+#[test]
+fn test_sell_fee_routed_to_treasury() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let treasury_id = env.register(Treasury, ());
+    let amm_id = env.register(AMM, ());
+
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+    let amm_client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let usdc_admin = Address::generate(&env);
+    let usdc_token = env.register_stellar_asset_contract_v2(usdc_admin).address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc_token);
+
+    treasury_client.initialize(&admin, &usdc_token, &Address::generate(&env));
+    amm_client.initialize(
+        &admin,
+        &Address::generate(&env),
+        &usdc_token,
+        &100_000_000_000u128,
+    );
+    amm_client.set_treasury(&treasury_id);
+
+    let market_id = BytesN::from_array(&env, &[6u8; 32]);
+    let lp = Address::generate(&env);
+    usdc_client.mint(&lp, &10_000_000);
+    amm_client.create_pool(&lp, &market_id, &10_000_000u128);
+
+    let trader = Address::generate(&env);
+    usdc_client.mint(&trader, &100_000);
+    let shares = amm_client.buy_shares(&trader, &market_id, &1u32, &100_000u128, &1u128, &None);
+
+    // Buy fee (0.2%) already reached the treasury
+    assert_eq!(treasury_client.get_total_fees(), 200);
+
+    let (yes_reserve, no_reserve, _, _, _) = amm_client.get_pool_state(&market_id);
+    let payout = (shares * no_reserve) / (yes_reserve + shares);
+    let sell_fee = (payout * 20) / 10000;
+
+    let received = amm_client.sell_shares(&trader, &market_id, &1u32, &shares, &1u128);
+    assert_eq!(received, payout - sell_fee);
+    assert_eq!(treasury_client.get_total_fees(), 200 + sell_fee as i128);
+}
+
+/// Integration test: Buy fees reach the treasury configured via set_treasury
+#[test]
+fn test_set_treasury_routes_buy_fees() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let treasury_id = env.register(Treasury, ());
+    let amm_id = env.register(AMM, ());
+
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+    let amm_client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let usdc_admin = Address::generate(&env);
+    let usdc_token = env.register_stellar_asset_contract_v2(usdc_admin).address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc_token);
+
+    treasury_client.initialize(&admin, &usdc_token, &Address::generate(&env));
+    amm_client.initialize(
+        &admin,
+        &Address::generate(&env),
+        &usdc_token,
+        &100_000_000_000u128,
+    );
+
+    let market_id = BytesN::from_array(&env, &[8u8; 32]);
+    let lp = Address::generate(&env);
+    usdc_client.mint(&lp, &10_000_000);
+    amm_client.create_pool(&lp, &market_id, &10_000_000u128);
+
+    // Without a treasury the fee stays in the AMM
+    let trader = Address::generate(&env);
+    usdc_client.mint(&trader, &200_000);
+    amm_client.buy_shares(&trader, &market_id, &1u32, &100_000u128, &1u128, &None);
+    assert_eq!(treasury_client.get_total_fees(), 0);
+
+    amm_client.set_treasury(&treasury_id);
+    amm_client.buy_shares(&trader, &market_id, &1u32, &100_000u128, &1u128, &None);
+    assert_eq!(treasury_client.get_total_fees(), 200);
+
+    // Treasury cannot be swapped once set
+    assert!(amm_client
+        .try_set_treasury(&Address::generate(&env))
+        .is_err());
+}
+
+/// Integration test: Trade fees are tracked per market in the treasury
+#[test]
+fn test_treasury_tracks_fees_per_market() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let treasury_id = env.register(Treasury, ());
+    let amm_id = env.register(AMM, ());
+
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+    let amm_client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let usdc_admin = Address::generate(&env);
+    let usdc_token = env.register_stellar_asset_contract_v2(usdc_admin).address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc_token);
+
+    treasury_client.initialize(&admin, &usdc_token, &Address::generate(&env));
+    amm_client.initialize(
+        &admin,
+        &Address::generate(&env),
+        &usdc_token,
+        &100_000_000_000u128,
+    );
+    amm_client.set_treasury(&treasury_id);
+
+    let market_a = BytesN::from_array(&env, &[8u8; 32]);
+    let market_b = BytesN::from_array(&env, &[9u8; 32]);
+    let lp = Address::generate(&env);
+    usdc_client.mint(&lp, &20_000_000);
+    amm_client.create_pool(&lp, &market_a, &10_000_000u128);
+    amm_client.create_pool(&lp, &market_b, &10_000_000u128);
+
+    // 0.2% fee: 200 on market A, 100 on market B
+    let trader = Address::generate(&env);
+    usdc_client.mint(&trader, &150_000);
+    amm_client.buy_shares(&trader, &market_a, &1u32, &100_000u128, &1u128, &None);
+    amm_client.buy_shares(&trader, &market_b, &0u32, &50_000u128, &1u128, &None);
+
+    assert_eq!(treasury_client.get_market_fees(&market_a), 200);
+    assert_eq!(treasury_client.get_market_fees(&market_b), 100);
+    assert_eq!(treasury_client.get_total_fees(), 300);
+}
+
+/// Integration test: Draining a pool sets aside its LPs' claims and splits the protocol's share
+/// across treasury fee pools
+#[test]
+fn test_drain_pool_flows_through_fee_split() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let factory_id = env.register(MarketFactory, ());
+    let treasury_id = env.register(Treasury, ());
+    let amm_id = env.register(AMM, ());
+
+    let factory_client = MarketFactoryClient::new(&env, &factory_id);
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+    let amm_client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let usdc_admin = Address::generate(&env);
+    let usdc_token = env.register_stellar_asset_contract_v2(usdc_admin).address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc_token);
+
+    factory_client.initialize(&admin, &usdc_token, &treasury_id);
+    treasury_client.initialize(&admin, &usdc_token, &factory_id);
+    amm_client.initialize(&admin, &factory_id, &usdc_token, &100_000_000_000u128);
+    amm_client.set_treasury(&treasury_id);
+
+    // Fund the treasury's platform pool and seed protocol-owned liquidity
+    let fee_source = Address::generate(&env);
+    usdc_client.mint(&fee_source, &10_000_000);
+    treasury_client.deposit_fees(&fee_source, &10_000_000, &None);
+    let market_id = BytesN::from_array(&env, &[9u8; 32]);
+    amm_client.bootstrap_pool(&market_id, &2_000_000u128);
+
+    // An outside LP matches the protocol's position
+    let lp = Address::generate(&env);
+    usdc_client.mint(&lp, &2_000_000);
+    amm_client.add_liquidity(&lp, &market_id, &2_000_000u128, &None, &0u32);
+
+    amm_client.cancel_pool(&market_id);
+    let pools_before = treasury_client.get_pools();
+    let drained = amm_client.drain_pool(&market_id);
+    assert_eq!(drained, 2_000_000);
+
+    // The LP claims its half; the protocol's half flows through the default ratios:
+    // 50% platform, 30% leaderboard, 20% creator
+    assert_eq!(
+        amm_client.claim_retired_liquidity(&lp, &market_id),
+        2_000_000
+    );
+    assert_eq!(
+        token::Client::new(&env, &usdc_token).balance(&lp),
+        2_000_000
+    );
+    let pools = treasury_client.get_pools();
+    assert_eq!(pools.platform - pools_before.platform, 1_000_000);
+    assert_eq!(pools.leaderboard - pools_before.leaderboard, 600_000);
+    assert_eq!(pools.creator - pools_before.creator, 400_000);
+    assert_eq!(pools.total - pools_before.total, 2_000_000);
+
+    let (yes_reserve, no_reserve, _, _, _) = amm_client.get_pool_state(&market_id);
+    assert_eq!((yes_reserve, no_reserve), (0, 0));
+    assert_eq!(amm_client.get_protocol_lp_tokens(&market_id), 0);
+}
+
+/// Integration test: stray USDC in the AMM is swept to the treasury, pool backing is not
+#[test]
+fn test_sweep_dust_moves_stray_usdc_to_treasury() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let treasury_id = env.register(Treasury, ());
+    let amm_id = env.register(AMM, ());
+
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+    let amm_client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let usdc_admin = Address::generate(&env);
+    let usdc_token = env.register_stellar_asset_contract_v2(usdc_admin).address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc_token);
+    let token_client = token::Client::new(&env, &usdc_token);
+
+    treasury_client.initialize(&admin, &usdc_token, &Address::generate(&env));
+    amm_client.initialize(
+        &admin,
+        &Address::generate(&env),
+        &usdc_token,
+        &100_000_000_000u128,
+    );
+    amm_client.set_treasury(&treasury_id);
+
+    let market_id = BytesN::from_array(&env, &[9u8; 32]);
+    let lp = Address::generate(&env);
+    usdc_client.mint(&lp, &10_000_000);
+    amm_client.create_pool(&lp, &market_id, &10_000_000u128);
+
+    let trader = Address::generate(&env);
+    usdc_client.mint(&trader, &500_000);
+    amm_client.buy_shares(&trader, &market_id, &1u32, &500_000u128, &1u128, &None);
+
+    // Nothing stray yet
+    assert_eq!(amm_client.sweep_dust(), 0);
+
+    // A mistaken direct transfer to the AMM
+    usdc_client.mint(&amm_id, &12_345);
+    let amm_balance_before = token_client.balance(&amm_id);
+
+    assert_eq!(amm_client.sweep_dust(), 12_345);
+    assert_eq!(token_client.balance(&amm_id), amm_balance_before - 12_345);
+    assert_eq!(amm_client.sweep_dust(), 0);
+}
+
+/// Integration test: sweep_dust counts the reserves of every pool as liabilities
+#[test]
+fn test_sweep_dust_leaves_backing_of_many_pools() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let treasury_id = env.register(Treasury, ());
+    let amm_id = env.register(AMM, ());
+
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+    let amm_client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let usdc_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc_token);
+
+    treasury_client.initialize(&admin, &usdc_token, &Address::generate(&env));
+    amm_client.initialize(
+        &admin,
+        &Address::generate(&env),
+        &usdc_token,
+        &100_000_000_000u128,
+    );
+    amm_client.set_treasury(&treasury_id);
+
+    // Enough pools that a capped scan of the pool index would miss some
+    let pool_count = 210u32;
+    let lp = Address::generate(&env);
+    usdc_client.mint(&lp, &(pool_count as i128 * 1_000));
+    for i in 0..pool_count {
+        let mut id = [0u8; 32];
+        id[..4].copy_from_slice(&i.to_be_bytes());
+        amm_client.create_pool(&lp, &BytesN::from_array(&env, &id), &1_000u128);
+    }
+    assert_eq!(
+        amm_client.get_total_value_locked(),
+        pool_count as u128 * 1_000
+    );
+
+    usdc_client.mint(&amm_id, &77);
+    assert_eq!(amm_client.sweep_dust(), 77);
+}
+
+/// Integration test: AMM rejects trades once the oracle finalizes the market
+#[test]
+#[should_panic(expected = "market resolved")]
+fn test_amm_blocks_trading_on_resolved_market() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let oracle_id = env.register(OracleManager, ());
+    let amm_id = env.register(AMM, ());
+    let market_contract_id = env.register(PredictionMarket, ());
+
+    let oracle_client = OracleManagerClient::new(&env, &oracle_id);
+    let amm_client = AMMClient::new(&env, &amm_id);
+    let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+
+    let admin = Address::generate(&env);
+    let usdc_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc_token);
+
+    oracle_client.initialize(&admin, &2u32);
+    amm_client.initialize(
+        &admin,
+        &Address::generate(&env),
+        &usdc_token,
+        &100_000_000_000u128,
+    );
+    amm_client.set_oracle(&oracle_id);
+
+    let market_id = BytesN::from_array(&env, &[10u8; 32]);
+    let closing_time = 500u64;
+    let resolution_time = 1000u64;
+    market_client.initialize(
+        &market_id,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &usdc_token,
+        &oracle_id,
+        &closing_time,
+        &resolution_time,
+    );
+    let oracle1 = Address::generate(&env);
+    let oracle2 = Address::generate(&env);
+    oracle_client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
+    oracle_client.register_oracle(&oracle2, &Symbol::new(&env, "Oracle2"));
+    oracle_client.register_market(&market_id, &resolution_time, &2u32);
+
+    let lp = Address::generate(&env);
+    usdc_client.mint(&lp, &10_000_000);
+    amm_client.create_pool(&lp, &market_id, &10_000_000u128);
+
+    // Trading works before resolution
+    let trader = Address::generate(&env);
+    usdc_client.mint(&trader, &200_000);
+    amm_client.buy_shares(&trader, &market_id, &1u32, &100_000u128, &1u128, &None);
+
+    // Oracles reach consensus and the resolution is finalized
+    env.ledger().set_timestamp(closing_time + 10);
+    market_client.close_market(&market_id);
+    env.ledger().set_timestamp(resolution_time + 10);
+    let data_hash = BytesN::from_array(&env, &[0u8; 32]);
+    oracle_client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash, &None);
+    oracle_client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash, &None);
+    env.ledger().set_timestamp(resolution_time + 604800 + 10);
+    oracle_client.finalize_resolution(&market_id, &market_contract_id, &Address::generate(&env));
+    assert!(oracle_client.is_finalized(&market_id));
+
+    amm_client.buy_shares(&trader, &market_id, &1u32, &100_000u128, &1u128, &None);
+}
+
+/// Integration test: redeeming winning shares honours the caller's payout floor
+#[test]
+fn test_redeem_winning_shares_enforces_min_payout() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let oracle_id = env.register(OracleManager, ());
+    let amm_id = env.register(AMM, ());
+    let market_contract_id = env.register(PredictionMarket, ());
+
+    let oracle_client = OracleManagerClient::new(&env, &oracle_id);
+    let amm_client = AMMClient::new(&env, &amm_id);
+    let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+
+    let admin = Address::generate(&env);
+    let usdc_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc_token);
+
+    oracle_client.initialize(&admin, &2u32);
+    amm_client.initialize(
+        &admin,
+        &Address::generate(&env),
+        &usdc_token,
+        &100_000_000_000u128,
+    );
+    amm_client.set_oracle(&oracle_id);
+
+    let market_id = BytesN::from_array(&env, &[14u8; 32]);
+    let closing_time = 500u64;
+    let resolution_time = 1000u64;
+    market_client.initialize(
+        &market_id,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &usdc_token,
+        &oracle_id,
+        &closing_time,
+        &resolution_time,
+    );
+    let oracle1 = Address::generate(&env);
+    let oracle2 = Address::generate(&env);
+    oracle_client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
+    oracle_client.register_oracle(&oracle2, &Symbol::new(&env, "Oracle2"));
+    oracle_client.register_market(&market_id, &resolution_time, &2u32);
+
+    let lp = Address::generate(&env);
+    usdc_client.mint(&lp, &10_000_000);
+    amm_client.create_pool(&lp, &market_id, &10_000_000u128);
+
+    let trader = Address::generate(&env);
+    usdc_client.mint(&trader, &100_000);
+    let shares = amm_client.buy_shares(&trader, &market_id, &1u32, &100_000u128, &1u128, &None);
+
+    // YES wins and the resolution is finalized
+    env.ledger().set_timestamp(closing_time + 10);
+    market_client.close_market(&market_id);
+    env.ledger().set_timestamp(resolution_time + 10);
+    let data_hash = BytesN::from_array(&env, &[0u8; 32]);
+    oracle_client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash, &None);
+    oracle_client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash, &None);
+    env.ledger().set_timestamp(resolution_time + 604800 + 10);
+    oracle_client.finalize_resolution(&market_id, &market_contract_id, &Address::generate(&env));
+
+    // A floor above the 1:1 payout reverts and leaves the shares intact
+    let expected = amm_client.estimate_payout(&trader, &market_id, &1u32);
+    assert_eq!(expected, shares);
+    let result = amm_client.try_redeem_winning_shares(&trader, &market_id, &(expected + 1));
+    assert!(result.is_err());
+
+    let pool_before = amm_client.get_pool_state(&market_id);
+    let payout = amm_client.redeem_winning_shares(&trader, &market_id, &expected);
+    assert_eq!(payout, expected);
+    // The payout comes out of the share liability; LP reserves are untouched
+    assert_eq!(amm_client.get_pool_state(&market_id), pool_before);
+    assert_eq!(
+        token::Client::new(&env, &usdc_token).balance(&trader),
+        expected as i128
+    );
+    assert_eq!(amm_client.estimate_payout(&trader, &market_id, &1u32), 0);
+}
+
+/// Integration test: a keeper drains a resolved pool only after it has gone stale
+#[test]
+fn test_auto_drain_only_after_inactivity() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let oracle_id = env.register(OracleManager, ());
+    let amm_id = env.register(AMM, ());
+    let treasury_id = env.register(Treasury, ());
+    let market_contract_id = env.register(PredictionMarket, ());
+
+    let oracle_client = OracleManagerClient::new(&env, &oracle_id);
+    let amm_client = AMMClient::new(&env, &amm_id);
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+    let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+
+    let admin = Address::generate(&env);
+    let usdc_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc_token);
+
+    oracle_client.initialize(&admin, &2u32);
+    treasury_client.initialize(&admin, &usdc_token, &Address::generate(&env));
+    amm_client.initialize(
+        &admin,
+        &Address::generate(&env),
+        &usdc_token,
+        &100_000_000_000u128,
+    );
+    amm_client.set_oracle(&oracle_id);
+    amm_client.set_treasury(&treasury_id);
+    amm_client.set_stale_drain_config(&2_592_000u64, &100u32);
+
+    let market_id = BytesN::from_array(&env, &[15u8; 32]);
+    let closing_time = 500u64;
+    let resolution_time = 1000u64;
+    market_client.initialize(
+        &market_id,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &usdc_token,
+        &oracle_id,
+        &closing_time,
+        &resolution_time,
+    );
+    let oracle1 = Address::generate(&env);
+    let oracle2 = Address::generate(&env);
+    oracle_client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
+    oracle_client.register_oracle(&oracle2, &Symbol::new(&env, "Oracle2"));
+    oracle_client.register_market(&market_id, &resolution_time, &2u32);
+
+    let lp = Address::generate(&env);
+    usdc_client.mint(&lp, &10_000_000);
+    amm_client.create_pool(&lp, &market_id, &10_000_000u128);
+
+    let trader = Address::generate(&env);
+    usdc_client.mint(&trader, &100_000);
+    let shares = amm_client.buy_shares(&trader, &market_id, &1u32, &100_000u128, &1u128, &None);
+    let (yes_reserve, no_reserve, _, _, _) = amm_client.get_pool_state(&market_id);
+    let reserves = yes_reserve + no_reserve;
+
+    env.ledger().set_timestamp(closing_time + 10);
+    market_client.close_market(&market_id);
+    env.ledger().set_timestamp(resolution_time + 10);
+    let data_hash = BytesN::from_array(&env, &[0u8; 32]);
+    oracle_client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash, &None);
+    oracle_client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash, &None);
+    env.ledger().set_timestamp(resolution_time + 604800 + 10);
+    oracle_client.finalize_resolution(&market_id, &market_contract_id, &Address::generate(&env));
+
+    // Resolved, but the pool saw activity within the last 30 days
+    let keeper = Address::generate(&env);
+    let result = amm_client.try_auto_drain_if_stale(&keeper, &market_id);
+    assert!(result.is_err());
+
+    env.ledger().set_timestamp(2_592_000 + 1);
+    let fees_before = treasury_client.get_pools().total;
+    let drained = amm_client.auto_drain_if_stale(&keeper, &market_id);
+    assert_eq!(drained, reserves);
+
+    // 1% to the keeper and the rest is claimable by the sole LP; nothing is left
+    // for the treasury
+    let token_client = token::Client::new(&env, &usdc_token);
+    let keeper_reward = token_client.balance(&keeper);
+    assert_eq!(keeper_reward, (reserves / 100) as i128);
+    amm_client.claim_retired_liquidity(&lp, &market_id);
+    assert_eq!(token_client.balance(&lp), reserves as i128 - keeper_reward);
+    assert_eq!(treasury_client.get_pools().total, fees_before);
+    let (yes_reserve, no_reserve, _, _, _) = amm_client.get_pool_state(&market_id);
+    assert_eq!((yes_reserve, no_reserve), (0, 0));
+    assert_eq!(amm_client.get_lp_balance(&market_id, &lp), 0);
+
+    // The winner's shares were not swept and still redeem in full
+    assert_eq!(
+        amm_client.redeem_winning_shares(&trader, &market_id, &shares),
+        shares
+    );
+    assert_eq!(token_client.balance(&amm_id), 0);
+}
+
+/// Integration test: a cancelled market's stale pool can be auto-drained and its
+/// shares refunded
+#[test]
+fn test_auto_drain_cancelled_pool_keeps_shares_redeemable() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let amm_id = env.register(AMM, ());
+    let treasury_id = env.register(Treasury, ());
+    let amm_client = AMMClient::new(&env, &amm_id);
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+
+    let admin = Address::generate(&env);
+    let usdc_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc_token);
+
+    treasury_client.initialize(&admin, &usdc_token, &Address::generate(&env));
+    amm_client.initialize(
+        &admin,
+        &Address::generate(&env),
+        &usdc_token,
+        &100_000_000_000u128,
+    );
+    amm_client.set_treasury(&treasury_id);
+    amm_client.set_stale_drain_config(&2_592_000u64, &0u32);
+
+    let market_id = BytesN::from_array(&env, &[16u8; 32]);
+    let lp = Address::generate(&env);
+    usdc_client.mint(&lp, &10_000_000);
+    amm_client.create_pool(&lp, &market_id, &10_000_000u128);
+
+    let trader = Address::generate(&env);
+    usdc_client.mint(&trader, &100_000);
+    let yes = amm_client.buy_shares(&trader, &market_id, &1u32, &50_000u128, &1u128, &None);
+    let no = amm_client.buy_shares(&trader, &market_id, &0u32, &50_000u128, &1u128, &None);
+
+    // Not cancelled yet, and there is no oracle resolution either
+    let result = amm_client.try_redeem_cancelled_shares(&trader, &market_id);
+    assert!(result.is_err());
+
+    amm_client.cancel_pool(&market_id);
+    let result = amm_client.try_buy_shares(&trader, &market_id, &1u32, &1_000u128, &1u128, &None);
+    assert!(result.is_err());
+
+    env.ledger().set_timestamp(2_592_000 + 1);
+    amm_client.auto_drain_if_stale(&Address::generate(&env), &market_id);
+    amm_client.claim_retired_liquidity(&lp, &market_id);
+
+    // Both sides refund 1:1 from the share liability
+    assert_eq!(
+        amm_client.redeem_cancelled_shares(&trader, &market_id),
+        yes + no
+    );
+    assert_eq!(token::Client::new(&env, &usdc_token).balance(&amm_id), 0);
+}
+
+/// Integration test: any keeper can finalize a ready market and is paid by the treasury
+#[test]
+fn test_keeper_finalizes_resolution_for_reward() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let oracle_id = env.register(OracleManager, ());
+    let treasury_id = env.register(Treasury, ());
+    let market_contract_id = env.register(PredictionMarket, ());
+
+    let oracle_client = OracleManagerClient::new(&env, &oracle_id);
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+    let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+
+    let admin = Address::generate(&env);
+    let usdc_admin = Address::generate(&env);
+    let usdc_token = env.register_stellar_asset_contract_v2(usdc_admin).address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc_token);
+    let usdc_balance = token::Client::new(&env, &usdc_token);
+
+    treasury_client.initialize(&admin, &usdc_token, &Address::generate(&env));
+    oracle_client.initialize(&admin, &2u32);
+    oracle_client.set_treasury(&treasury_id);
+    treasury_client.set_keeper_reward(&oracle_id, &1_000i128);
+
+    // Fund the platform pool from collected fees
+    let fee_payer = Address::generate(&env);
+    usdc_client.mint(&fee_payer, &100_000);
+    treasury_client.deposit_fees(&fee_payer, &100_000i128, &None);
+
+    let market_id = BytesN::from_array(&env, &[11u8; 32]);
+    let closing_time = 500u64;
+    let resolution_time = 1000u64;
+    market_client.initialize(
+        &market_id,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &usdc_token,
+        &oracle_id,
+        &closing_time,
+        &resolution_time,
+    );
+    let oracle1 = Address::generate(&env);
+    let oracle2 = Address::generate(&env);
+    oracle_client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
+    oracle_client.register_oracle(&oracle2, &Symbol::new(&env, "Oracle2"));
+    oracle_client.register_market(&market_id, &resolution_time, &2u32);
+
+    env.ledger().set_timestamp(closing_time + 10);
+    market_client.close_market(&market_id);
+    env.ledger().set_timestamp(resolution_time + 10);
+    let data_hash = BytesN::from_array(&env, &[0u8; 32]);
+    oracle_client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash, &None);
+    oracle_client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash, &None);
+    env.ledger().set_timestamp(resolution_time + 604800 + 10);
+
+    // A non-admin keeper finalizes and is rewarded
+    let keeper = Address::generate(&env);
+    oracle_client.finalize_resolution(&market_id, &market_contract_id, &keeper);
+    assert!(oracle_client.is_finalized(&market_id));
+    assert_eq!(usdc_balance.balance(&keeper), 1_000);
+
+    // A second finalization is rejected
+    let result = oracle_client.try_finalize_resolution(&market_id, &market_contract_id, &keeper);
+    assert!(result.is_err());
+    assert_eq!(usdc_balance.balance(&keeper), 1_000);
+}
+
+/// Integration test: a treasury that cannot pay the keeper does not block finalization
+#[test]
+fn test_finalize_resolution_survives_unconfigured_keeper_reward() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let oracle_id = env.register(OracleManager, ());
+    let treasury_id = env.register(Treasury, ());
+    let market_contract_id = env.register(PredictionMarket, ());
+
+    let oracle_client = OracleManagerClient::new(&env, &oracle_id);
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+    let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+
+    let admin = Address::generate(&env);
+    let usdc_admin = Address::generate(&env);
+    let usdc_token = env.register_stellar_asset_contract_v2(usdc_admin).address();
+    let usdc_balance = token::Client::new(&env, &usdc_token);
+
+    // The treasury never gets set_keeper_reward, so pay_keeper_reward panics
+    treasury_client.initialize(&admin, &usdc_token, &Address::generate(&env));
+    oracle_client.initialize(&admin, &2u32);
+    oracle_client.set_treasury(&treasury_id);
+
+    let market_id = BytesN::from_array(&env, &[12u8; 32]);
+    let closing_time = 500u64;
+    let resolution_time = 1000u64;
+    market_client.initialize(
+        &market_id,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &usdc_token,
+        &oracle_id,
+        &closing_time,
+        &resolution_time,
+    );
+    let oracle1 = Address::generate(&env);
+    let oracle2 = Address::generate(&env);
+    oracle_client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
+    oracle_client.register_oracle(&oracle2, &Symbol::new(&env, "Oracle2"));
+    oracle_client.register_market(&market_id, &resolution_time, &2u32);
+
+    env.ledger().set_timestamp(closing_time + 10);
+    market_client.close_market(&market_id);
+    env.ledger().set_timestamp(resolution_time + 10);
+    let data_hash = BytesN::from_array(&env, &[0u8; 32]);
+    oracle_client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash, &None);
+    oracle_client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash, &None);
+    env.ledger().set_timestamp(resolution_time + 604800 + 10);
+
+    // Finalization succeeds without paying the keeper
+    let keeper = Address::generate(&env);
+    oracle_client.finalize_resolution(&market_id, &market_contract_id, &keeper);
+    assert!(oracle_client.is_finalized(&market_id));
+    assert_eq!(usdc_balance.balance(&keeper), 0);
+}
+
+/// Register a PredictionMarket for `market_id` and close it, so the oracle
+/// can resolve it once the ledger is past `closing_time`
+fn register_closed_market(
+    env: &Env,
+    market_id: &BytesN<32>,
+    usdc_token: &Address,
+    oracle_id: &Address,
+    closing_time: u64,
+    resolution_time: u64,
+) -> Address {
+    let market_contract_id = env.register(PredictionMarket, ());
+    let market_client = PredictionMarketClient::new(env, &market_contract_id);
+    market_client.initialize(
+        market_id,
+        &Address::generate(env),
+        &Address::generate(env),
+        usdc_token,
+        oracle_id,
+        &closing_time,
+        &resolution_time,
+    );
+    market_client.close_market(market_id);
+    market_contract_id
+}
+
+/// Integration test: Creators with resolved markets pay a discounted creation fee
+#[test]
+fn test_creation_fee_discount_for_resolved_creators() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let factory_id = env.register(MarketFactory, ());
+    let treasury_id = env.register(Treasury, ());
+    let oracle_id = env.register(OracleManager, ());
+
+    let factory_client = MarketFactoryClient::new(&env, &factory_id);
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+    let oracle_client = OracleManagerClient::new(&env, &oracle_id);
+
+    let admin = Address::generate(&env);
+    let usdc_admin = Address::generate(&env);
+    let usdc_token = env.register_stellar_asset_contract_v2(usdc_admin).address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc_token);
+    let usdc_balance = token::Client::new(&env, &usdc_token);
+
+    factory_client.initialize(&admin, &usdc_token, &treasury_id);
+    treasury_client.initialize(&admin, &usdc_token, &factory_id);
+    oracle_client.initialize(&admin, &2u32);
+    factory_client.set_oracle(&oracle_id);
+    // 25% off per resolved market, paying at least half the fee
+    factory_client.set_creation_discount(&2_500u32, &5_000u32);
+
+    let oracle1 = Address::generate(&env);
+    let oracle2 = Address::generate(&env);
+    oracle_client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
+    oracle_client.register_oracle(&oracle2, &Symbol::new(&env, "Oracle2"));
+
+    let veteran = Address::generate(&env);
+    let newcomer = Address::generate(&env);
+    usdc_client.mint(&veteran, &100_000_000);
+    usdc_client.mint(&newcomer, &100_000_000);
+
+    let create = |creator: &Address, closing_time: u64| {
+        factory_client.create_market(
+            creator,
+            &Symbol::new(&env, "Mayweather"),
+            &String::from_str(&env, "MayweatherWins"),
+            &Symbol::new(&env, "Boxing"),
+            &String::from_str(&env, "YesIfMayweatherWins"),
+            &closing_time,
+            &(closing_time + 100),
+        )
+    };
+
+    // The veteran's first three markets are resolved and recorded
+    let data_hash = BytesN::from_array(&env, &[0u8; 32]);
+    let mut past_markets = soroban_sdk::Vec::new(&env);
+    for _ in 0..3 {
+        let market_id = create(&veteran, 100);
+        oracle_client.register_market(&market_id, &200u64, &2u32);
+        past_markets.push_back(market_id);
+    }
+    env.ledger().set_timestamp(210);
+    let mut market_contracts = soroban_sdk::Vec::new(&env);
+    for market_id in past_markets.iter() {
+        market_contracts.push_back(register_closed_market(
+            &env,
+            &market_id,
+            &usdc_token,
+            &oracle_id,
+            100,
+            200,
+        ));
+        oracle_client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash, &None);
+        oracle_client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash, &None);
+    }
+    env.ledger().set_timestamp(200 + 604800 + 10);
+    for (market_id, market_contract_id) in past_markets.iter().zip(market_contracts.iter()) {
+        oracle_client.finalize_resolution(
+            &market_id,
+            &market_contract_id,
+            &Address::generate(&env),
+        );
+        factory_client.record_market_resolved(&market_id);
+    }
+    assert_eq!(factory_client.get_creator_resolved_count(&veteran), 3);
+
+    // Full fee is 1 USDC (10^7); three resolutions would be 75% off but the floor is 50%
+    let now = env.ledger().timestamp();
+    let veteran_before = usdc_balance.balance(&veteran);
+    let newcomer_before = usdc_balance.balance(&newcomer);
+    create(&veteran, now + 100);
+    create(&newcomer, now + 100);
+    assert_eq!(veteran_before - usdc_balance.balance(&veteran), 5_000_000);
+    assert_eq!(
+        newcomer_before - usdc_balance.balance(&newcomer),
+        10_000_000
+    );
+}
+
+/// Integration test: Factory combines oracle votes with its resolution record
+#[test]
+fn test_factory_reports_resolution_detail() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let factory_id = env.register(MarketFactory, ());
+    let treasury_id = env.register(Treasury, ());
+    let oracle_id = env.register(OracleManager, ());
+
+    let factory_client = MarketFactoryClient::new(&env, &factory_id);
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+    let oracle_client = OracleManagerClient::new(&env, &oracle_id);
+
+    let admin = Address::generate(&env);
+    let usdc_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc_token);
+
+    factory_client.initialize(&admin, &usdc_token, &treasury_id);
+    treasury_client.initialize(&admin, &usdc_token, &factory_id);
+    oracle_client.initialize(&admin, &2u32);
+    factory_client.set_oracle(&oracle_id);
+
+    let oracle1 = Address::generate(&env);
+    let oracle2 = Address::generate(&env);
+    let oracle3 = Address::generate(&env);
+    oracle_client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
+    oracle_client.register_oracle(&oracle2, &Symbol::new(&env, "Oracle2"));
+    oracle_client.register_oracle(&oracle3, &Symbol::new(&env, "Oracle3"));
+
+    let creator = Address::generate(&env);
+    usdc_client.mint(&creator, &100_000_000);
+    let market_id = factory_client.create_market(
+        &creator,
+        &Symbol::new(&env, "Mayweather"),
+        &String::from_str(&env, "MayweatherWins"),
+        &Symbol::new(&env, "Boxing"),
+        &String::from_str(&env, "YesIfMayweatherWins"),
+        &100u64,
+        &200u64,
+    );
+    oracle_client.register_market(&market_id, &200u64, &2u32);
+
+    // Two YES votes against one NO
+    env.ledger().set_timestamp(210);
+    let market_contract_id =
+        register_closed_market(&env, &market_id, &usdc_token, &oracle_id, 100, 200);
+    let data_hash = BytesN::from_array(&env, &[0u8; 32]);
+    oracle_client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash, &None);
+    oracle_client.submit_attestation(&oracle2, &market_id, &0u32, &data_hash, &None);
+    oracle_client.submit_attestation(&oracle3, &market_id, &1u32, &data_hash, &None);
+
+    let resolved_at = 200 + 604800 + 10;
+    env.ledger().set_timestamp(resolved_at);
+    oracle_client.finalize_resolution(&market_id, &market_contract_id, &Address::generate(&env));
+
+    let detail = factory_client.get_market_resolution_detail(&market_id);
+    assert_eq!(detail.winning_outcome, 1);
+    assert_eq!((detail.yes_count, detail.no_count), (2, 1));
+    assert_eq!(
+        detail.voters,
+        soroban_sdk::Vec::from_array(&env, [oracle1, oracle2, oracle3])
+    );
+    assert_eq!(detail.resolved_at, None);
+
+    factory_client.record_market_resolved(&market_id);
+    let detail = factory_client.get_market_resolution_detail(&market_id);
+    assert_eq!(detail.resolved_at, Some(resolved_at));
+}
+
+/// Integration test: Factory reports trading volume recorded by the AMM
+#[test]
+fn test_factory_reports_market_volume() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let factory_id = env.register(MarketFactory, ());
+    let amm_id = env.register(AMM, ());
+
+    let factory_client = MarketFactoryClient::new(&env, &factory_id);
+    let amm_client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let usdc_admin = Address::generate(&env);
+    let usdc_token = env.register_stellar_asset_contract_v2(usdc_admin).address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc_token);
+
+    factory_client.initialize(&admin, &usdc_token, &Address::generate(&env));
+    factory_client.set_amm(&amm_id);
+    amm_client.initialize(&admin, &factory_id, &usdc_token, &100_000_000_000u128);
+
+    let market_id = BytesN::from_array(&env, &[7u8; 32]);
+    assert_eq!(factory_client.get_market_volume(&market_id), 0);
+
+    let lp = Address::generate(&env);
+    usdc_client.mint(&lp, &10_000_000);
+    amm_client.create_pool(&lp, &market_id, &10_000_000u128);
+
+    let trader = Address::generate(&env);
+    usdc_client.mint(&trader, &150_000);
+    amm_client.buy_shares(&trader, &market_id, &1u32, &100_000u128, &1u128, &None);
+    amm_client.buy_shares(&trader, &market_id, &0u32, &50_000u128, &1u128, &None);
+
+    assert_eq!(amm_client.get_volume(&market_id), 150_000);
+    assert_eq!(factory_client.get_market_volume(&market_id), 150_000);
+}
+
+/// Integration test: Factory reports a market's liquidity depth from the AMM
+#[test]
+fn test_factory_reports_market_liquidity() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let factory_id = env.register(MarketFactory, ());
+    let amm_id = env.register(AMM, ());
+
+    let factory_client = MarketFactoryClient::new(&env, &factory_id);
+    let amm_client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let usdc_admin = Address::generate(&env);
+    let usdc_token = env.register_stellar_asset_contract_v2(usdc_admin).address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc_token);
+
+    factory_client.initialize(&admin, &usdc_token, &Address::generate(&env));
+    factory_client.set_amm(&amm_id);
+    amm_client.initialize(&admin, &factory_id, &usdc_token, &100_000_000_000u128);
+
+    let market_id = BytesN::from_array(&env, &[7u8; 32]);
+    assert_eq!(factory_client.get_market_liquidity(&market_id), 0);
+
+    let lp = Address::generate(&env);
+    usdc_client.mint(&lp, &10_000_000);
+    amm_client.create_pool(&lp, &market_id, &10_000_000u128);
+
+    let trader = Address::generate(&env);
+    usdc_client.mint(&trader, &100_000);
+    amm_client.buy_shares(&trader, &market_id, &1u32, &100_000u128, &1u128, &None);
+
+    let (yes_reserve, no_reserve, _, _, _) = amm_client.get_pool_state(&market_id);
+    assert_eq!(
+        factory_client.get_market_liquidity(&market_id),
+        yes_reserve + no_reserve
+    );
+}
+
+/// Integration test: Trading in a creator's market accrues claimable creator rewards
+#[test]
+fn test_trade_fees_credit_market_creator() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let factory_id = env.register(MarketFactory, ());
+    let treasury_id = env.register(Treasury, ());
+    let amm_id = env.register(AMM, ());
+
+    let factory_client = MarketFactoryClient::new(&env, &factory_id);
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+    let amm_client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let usdc_admin = Address::generate(&env);
+    let usdc_token = env.register_stellar_asset_contract_v2(usdc_admin).address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc_token);
+    let usdc_balance = token::Client::new(&env, &usdc_token);
+
+    factory_client.initialize(&admin, &usdc_token, &treasury_id);
+    treasury_client.initialize(&admin, &usdc_token, &factory_id);
+    amm_client.initialize(&admin, &factory_id, &usdc_token, &100_000_000_000u128);
+    amm_client.set_treasury(&treasury_id);
+    amm_client.set_creator_fee_share(&5_000u32); // 50% of trade fees
+
+    // Creator creates a market (pays the 1 USDC creation fee)
+    let creator = Address::generate(&env);
+    usdc_client.mint(&creator, &10_000_000);
+    let market_id = factory_client.create_market(
+        &creator,
+        &Symbol::new(&env, "Mayweather"),
+        &String::from_str(&env, "MayweatherWins"),
+        &Symbol::new(&env, "Boxing"),
+        &String::from_str(&env, "YesIfMayweatherWins"),
+        &1_000u64,
+        &2_000u64,
+    );
+    assert_eq!(treasury_client.get_creator_rewards(&creator), 0);
+
+    let lp = Address::generate(&env);
+    usdc_client.mint(&lp, &10_000_000);
+    amm_client.create_pool(&lp, &market_id, &10_000_000u128);
+
+    // 0.2% fee on 100_000 = 200, half attributed to the creator
+    let trader = Address::generate(&env);
+    usdc_client.mint(&trader, &100_000);
+    amm_client.buy_shares(&trader, &market_id, &1u32, &100_000u128, &1u128, &None);
+    assert_eq!(treasury_client.get_creator_rewards(&creator), 100);
+
+    assert_eq!(treasury_client.claim_creator_rewards(&creator), 100);
+    assert_eq!(usdc_balance.balance(&creator), 100);
+    assert_eq!(treasury_client.get_creator_rewards(&creator), 0);
+}
+
+/// Integration test: Market creation and pool seeding succeed or fail together
+#[test]
+fn test_create_market_with_pool_is_atomic() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let factory_id = env.register(MarketFactory, ());
+    let treasury_id = env.register(Treasury, ());
+    let amm_id = env.register(AMM, ());
+
+    let factory_client = MarketFactoryClient::new(&env, &factory_id);
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+    let amm_client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let usdc_admin = Address::generate(&env);
+    let usdc_token = env.register_stellar_asset_contract_v2(usdc_admin).address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc_token);
+
+    factory_client.initialize(&admin, &usdc_token, &treasury_id);
+    factory_client.set_amm(&amm_id);
+    treasury_client.initialize(&admin, &usdc_token, &factory_id);
+    amm_client.initialize(&admin, &factory_id, &usdc_token, &100_000_000_000u128);
+
+    let creator = Address::generate(&env);
+    usdc_client.mint(&creator, &20_000_000);
+
+    // Seeding more than the creator holds fails, leaving no market behind
+    let result = factory_client.try_create_market_with_pool(
+        &creator,
+        &Symbol::new(&env, "Mayweather"),
+        &String::from_str(&env, "MayweatherWins"),
+        &Symbol::new(&env, "Boxing"),
+        &String::from_str(&env, "YesIfMayweatherWins"),
+        &1_000u64,
+        &2_000u64,
+        &50_000_000u128,
+    );
+    assert!(result.is_err());
+    assert_eq!(factory_client.get_market_count(), 0);
+    assert_eq!(factory_client.get_creator_market_count(&creator), 0);
+
+    // A fundable pool creates both in one call
+    let market_id = factory_client.create_market_with_pool(
+        &creator,
+        &Symbol::new(&env, "Mayweather"),
+        &String::from_str(&env, "MayweatherWins"),
+        &Symbol::new(&env, "Boxing"),
+        &String::from_str(&env, "YesIfMayweatherWins"),
+        &1_000u64,
+        &2_000u64,
+        &10_000_000u128,
+    );
+    assert_eq!(factory_client.get_market_count(), 1);
+    assert_eq!(factory_client.get_market_info(&market_id).creator, creator);
+    let (yes_reserve, no_reserve, _, _, _) = amm_client.get_pool_state(&market_id);
+    assert_eq!(yes_reserve, 5_000_000);
+    assert_eq!(no_reserve, 5_000_000);
+    assert_eq!(amm_client.get_lp_balance(&market_id, &creator), 10_000_000);
+}
+
+/// Integration test: LP-token transfer royalty is credited to the market creator
+#[test]
+fn test_lp_transfer_royalty_credits_creator() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let factory_id = env.register(MarketFactory, ());
+    let treasury_id = env.register(Treasury, ());
+    let amm_id = env.register(AMM, ());
+
+    let factory_client = MarketFactoryClient::new(&env, &factory_id);
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+    let amm_client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let usdc_admin = Address::generate(&env);
+    let usdc_token = env.register_stellar_asset_contract_v2(usdc_admin).address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc_token);
+
+    factory_client.initialize(&admin, &usdc_token, &treasury_id);
+    treasury_client.initialize(&admin, &usdc_token, &factory_id);
+    amm_client.initialize(&admin, &factory_id, &usdc_token, &100_000_000_000u128);
+    amm_client.set_treasury(&treasury_id);
+
+    let creator = Address::generate(&env);
+    usdc_client.mint(&creator, &10_000_000);
+    let market_id = factory_client.create_market(
+        &creator,
+        &Symbol::new(&env, "Mayweather"),
+        &String::from_str(&env, "MayweatherWins"),
+        &Symbol::new(&env, "Boxing"),
+        &String::from_str(&env, "YesIfMayweatherWins"),
+        &1_000u64,
+        &2_000u64,
+    );
+
+    let lp = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    usdc_client.mint(&lp, &10_000_000);
+    amm_client.create_pool(&lp, &market_id, &10_000_000u128);
+
+    // Free by default
+    assert_eq!(
+        amm_client.transfer_lp_tokens(&lp, &buyer, &market_id, &1_000_000u128),
+        1_000_000
+    );
+    assert_eq!(treasury_client.get_creator_rewards(&creator), 0);
+
+    // Royalties are capped at 5%
+    assert!(amm_client
+        .try_set_lp_transfer_royalty(&creator, &market_id, &501u32)
+        .is_err());
+
+    // Creator sets a 1% royalty
+    amm_client.set_lp_transfer_royalty(&creator, &market_id, &100u32);
+    let received = amm_client.transfer_lp_tokens(&lp, &buyer, &market_id, &1_000_000u128);
+
+    assert_eq!(received, 990_000);
+    assert_eq!(amm_client.get_lp_balance(&market_id, &buyer), 1_990_000);
+    assert_eq!(amm_client.get_lp_balance(&market_id, &lp), 8_000_000);
+    assert_eq!(treasury_client.get_creator_rewards(&creator), 10_000);
+}
+
 /// Integration test: Oracle consensus mechanism
 #[test]
 fn test_oracle_consensus_flow() {
@@ -180,6 +1414,52 @@ fn test_oracle_consensus_flow() {
     // Verify final outcome
 }
 
+/// Integration test: Factory reports a market as disputed while an oracle challenge is open
+#[test]
+fn test_factory_reports_disputed_resolution() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let factory_id = env.register(MarketFactory, ());
+    let oracle_id = env.register(OracleManager, ());
+
+    let factory_client = MarketFactoryClient::new(&env, &factory_id);
+    let oracle_client = OracleManagerClient::new(&env, &oracle_id);
+
+    let admin = Address::generate(&env);
+    let usdc_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let treasury = Address::generate(&env);
+
+    factory_client.initialize(&admin, &usdc_token, &treasury);
+    factory_client.set_oracle(&oracle_id);
+    oracle_client.initialize(&admin, &2u32);
+
+    let oracle1 = Address::generate(&env);
+    oracle_client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
+    oracle_client.register_oracle(&Address::generate(&env), &Symbol::new(&env, "Oracle2"));
+
+    let market_id = BytesN::from_array(&env, &[4u8; 32]);
+    let resolution_time = env.ledger().timestamp() + 100;
+    oracle_client.register_market(&market_id, &resolution_time, &2u32);
+    env.ledger()
+        .with_mut(|li| li.timestamp = resolution_time + 1);
+
+    let data_hash = BytesN::from_array(&env, &[5u8; 32]);
+    oracle_client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash, &None);
+    assert!(!factory_client.is_resolution_disputed(&market_id));
+
+    let challenger = Address::generate(&env);
+    oracle_client.challenge_attestation(
+        &challenger,
+        &oracle1,
+        &market_id,
+        &Symbol::new(&env, "fraud"),
+    );
+    assert!(factory_client.is_resolution_disputed(&market_id));
+}
+
 /// Integration test: Fee distribution flow
 #[test]
 fn test_fee_collection_and_distribution() {
@@ -2,8 +2,8 @@
 
 use boxmeout::market::{MarketError, PredictionMarketClient};
 use soroban_sdk::{
-    testutils::{Address as _, Ledger, LedgerInfo},
-    token, Address, BytesN, Env, Symbol,
+    testutils::{Address as _, Events, Ledger, LedgerInfo},
+    token, Address, BytesN, Env, Symbol, TryFromVal,
 };
 
 // ...rest of the file...
@@ -848,6 +848,43 @@ fn test_get_market_state_closed() {
     assert_eq!(state.winning_outcome, None); // Not resolved yet
 }
 
+#[test]
+fn test_get_market_state_auto_closes_after_closing_time() {
+    let env = create_test_env();
+    let (client, market_id, _creator, _admin, usdc_address, _market_contract) =
+        setup_test_market(&env);
+
+    // Advance time past closing time without calling close_market
+    env.ledger().set(LedgerInfo {
+        timestamp: env.ledger().timestamp() + 86400 + 1,
+        protocol_version: 23,
+        sequence_number: 11,
+        network_id: Default::default(),
+        base_reserve: 10,
+        min_temp_entry_ttl: 16,
+        min_persistent_entry_ttl: 16,
+        max_entry_ttl: 6312000,
+    });
+    assert_eq!(client.get_market_state_value(), Some(0)); // still stored as OPEN
+
+    // First interaction flips the state and emits MarketClosed
+    let state = client.get_market_state(&market_id);
+    assert_eq!(state.status, 1); // STATE_CLOSED
+    let closed_topic = Symbol::new(&env, "market_closed_event");
+    assert!(env.events().all().iter().any(|(source, topics, _)| {
+        source == client.address
+            && Symbol::try_from_val(&env, &topics.get(0).unwrap()).ok()
+                == Some(closed_topic.clone())
+    }));
+    assert_eq!(client.get_market_state_value(), Some(1));
+
+    // Trades are rejected once closed
+    let user = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc_address).mint(&user, &1_000);
+    let result = client.try_commit_prediction(&user, &BytesN::from_array(&env, &[2u8; 32]), &1_000);
+    assert_eq!(result, Err(Ok(MarketError::InvalidMarketState)));
+}
+
 #[test]
 fn test_get_market_state_resolved() {
     let env = create_test_env();
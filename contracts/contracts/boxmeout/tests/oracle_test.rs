@@ -28,6 +28,13 @@ fn register_oracle(env: &Env) -> Address {
     env.register(OracleManager, ())
 }
 
+// Register oracles that never attest so a market can meet the oracle-count requirement
+fn register_idle_oracles(env: &Env, client: &OracleManagerClient, count: u32) {
+    for _ in 0..count {
+        client.register_oracle(&Address::generate(env), &Symbol::new(env, "Idle"));
+    }
+}
+
 #[test]
 fn test_oracle_initialize() {
     let env = create_test_env();
@@ -44,6 +51,26 @@ fn test_oracle_initialize() {
     // Verify required_consensus stored correctly
 }
 
+#[test]
+#[should_panic(expected = "invalid consensus threshold")]
+fn test_oracle_initialize_zero_consensus_rejected() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let client = OracleManagerClient::new(&env, &register_oracle(&env));
+
+    client.initialize(&Address::generate(&env), &0u32);
+}
+
+#[test]
+fn test_oracle_initialize_single_consensus_accepted() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let client = OracleManagerClient::new(&env, &register_oracle(&env));
+
+    client.initialize(&Address::generate(&env), &1u32);
+    assert_eq!(client.get_config().required_consensus, 1);
+}
+
 #[test]
 fn test_register_oracle() {
     let env = create_test_env();
@@ -148,8 +175,10 @@ fn test_submit_attestation() {
     let market_id = BytesN::from_array(&env, &[1u8; 32]);
     let resolution_time = 1000u64;
 
+    register_idle_oracles(&env, &client, 1);
+
     // Register market with resolution time
-    client.register_market(&market_id, &resolution_time);
+    client.register_market(&market_id, &resolution_time, &2u32);
 
     // Set ledger time past resolution time
     env.ledger().set_timestamp(1001);
@@ -157,7 +186,7 @@ fn test_submit_attestation() {
     let result = 1u32; // YES
     let data_hash = BytesN::from_array(&env, &[0u8; 32]);
 
-    client.submit_attestation(&oracle1, &market_id, &result, &data_hash);
+    client.submit_attestation(&oracle1, &market_id, &result, &data_hash, &None);
 
     // Verify consensus is still false (need 2 votes)
     let (reached, outcome) = client.check_consensus(&market_id);
@@ -188,14 +217,14 @@ fn test_check_consensus_reached() {
     let resolution_time = 1000u64;
 
     // Register market and set timestamp past resolution time
-    client.register_market(&market_id, &resolution_time);
+    client.register_market(&market_id, &resolution_time, &2u32);
     env.ledger().set_timestamp(1001);
 
     let data_hash = BytesN::from_array(&env, &[0u8; 32]);
 
     // 2 oracles submit YES (1)
-    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash);
-    client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash);
+    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash, &None);
+    client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash, &None);
 
     // Verify consensus reached YES
     let (reached, outcome) = client.check_consensus(&market_id);
@@ -203,6 +232,52 @@ fn test_check_consensus_reached() {
     assert_eq!(outcome, 1);
 }
 
+#[test]
+fn test_check_consensus_is_pure_view() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let oracle_id = register_oracle(&env);
+    let client = OracleManagerClient::new(&env, &oracle_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &2u32);
+
+    let oracle1 = Address::generate(&env);
+    let oracle2 = Address::generate(&env);
+    client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
+    client.register_oracle(&oracle2, &Symbol::new(&env, "Oracle2"));
+
+    let market_id = BytesN::from_array(&env, &[1u8; 32]);
+    client.register_market(&market_id, &1000u64, &2u32);
+    env.ledger().set_timestamp(1001);
+
+    let data_hash = BytesN::from_array(&env, &[0u8; 32]);
+    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash, &None);
+    client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash, &None);
+
+    // Consensus outcome was persisted by submit_attestation
+    let reached_key = (Symbol::new(&env, "consensus_reached"), market_id.clone());
+    let result_key = (Symbol::new(&env, "consensus_result"), market_id.clone());
+    let read_state = || {
+        env.as_contract(&oracle_id, || {
+            (
+                env.storage().persistent().get::<_, u32>(&reached_key),
+                env.storage().persistent().has(&result_key),
+            )
+        })
+    };
+    let before = read_state();
+    assert_eq!(before, (Some(1u32), false));
+
+    // Polling repeatedly returns the same answer and leaves storage untouched
+    for _ in 0..3 {
+        assert_eq!(client.check_consensus(&market_id), (true, 1));
+        assert_eq!(read_state(), before);
+    }
+    assert_eq!(client.get_attestation_counts(&market_id), (2, 0));
+}
+
 #[test]
 fn test_check_consensus_not_reached() {
     let env = create_test_env();
@@ -222,14 +297,16 @@ fn test_check_consensus_not_reached() {
     let market_id = BytesN::from_array(&env, &[1u8; 32]);
     let resolution_time = 1000u64;
 
+    register_idle_oracles(&env, &client, 1);
+
     // Register market and set timestamp past resolution time
-    client.register_market(&market_id, &resolution_time);
+    client.register_market(&market_id, &resolution_time, &2u32);
     env.ledger().set_timestamp(1001);
 
     let data_hash = BytesN::from_array(&env, &[0u8; 32]);
 
-    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash);
-    client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash);
+    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash, &None);
+    client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash, &None);
 
     // Only 2 of 3 votes, consensus not reached
     let (reached, _) = client.check_consensus(&market_id);
@@ -271,16 +348,16 @@ fn test_check_consensus_tie_handling() {
     let resolution_time = 1000u64;
 
     // Register market and set timestamp past resolution time
-    client.register_market(&market_id, &resolution_time);
+    client.register_market(&market_id, &resolution_time, &2u32);
     env.ledger().set_timestamp(1001);
 
     let data_hash = BytesN::from_array(&env, &[0u8; 32]);
 
     // 2 vote YES, 2 vote NO
-    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash);
-    client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash);
-    client.submit_attestation(&oracle3, &market_id, &0u32, &data_hash);
-    client.submit_attestation(&oracle4, &market_id, &0u32, &data_hash);
+    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash, &None);
+    client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash, &None);
+    client.submit_attestation(&oracle3, &market_id, &0u32, &data_hash, &None);
+    client.submit_attestation(&oracle4, &market_id, &0u32, &data_hash, &None);
 
     // Both reached threshold 2, but it's a tie
     let (reached, _) = client.check_consensus(&market_id);
@@ -321,8 +398,10 @@ fn test_submit_attestation_stores_attestation() {
     let market_id = BytesN::from_array(&env, &[2u8; 32]);
     let resolution_time = 1000u64;
 
+    register_idle_oracles(&env, &client, 1);
+
     // Register market with resolution time
-    client.register_market(&market_id, &resolution_time);
+    client.register_market(&market_id, &resolution_time, &2u32);
 
     // Set ledger time past resolution time
     env.ledger().set_timestamp(1500);
@@ -330,7 +409,7 @@ fn test_submit_attestation_stores_attestation() {
     let result = 1u32; // YES
     let data_hash = BytesN::from_array(&env, &[0u8; 32]);
 
-    client.submit_attestation(&oracle1, &market_id, &result, &data_hash);
+    client.submit_attestation(&oracle1, &market_id, &result, &data_hash, &None);
 
     // Verify attestation is stored correctly
     let attestation = client.get_attestation(&market_id, &oracle1);
@@ -365,8 +444,10 @@ fn test_submit_attestation_non_attestor_rejected() {
     let market_id = BytesN::from_array(&env, &[3u8; 32]);
     let resolution_time = 1000u64;
 
+    register_idle_oracles(&env, &client, 2);
+
     // Register market
-    client.register_market(&market_id, &resolution_time);
+    client.register_market(&market_id, &resolution_time, &2u32);
 
     // Set ledger time past resolution time
     env.ledger().set_timestamp(1500);
@@ -374,7 +455,7 @@ fn test_submit_attestation_non_attestor_rejected() {
     let data_hash = BytesN::from_array(&env, &[0u8; 32]);
 
     // This should panic because oracle is not registered
-    client.submit_attestation(&unregistered_oracle, &market_id, &1u32, &data_hash);
+    client.submit_attestation(&unregistered_oracle, &market_id, &1u32, &data_hash, &None);
 }
 
 /// Cannot attest before resolution_time
@@ -396,8 +477,10 @@ fn test_submit_attestation_before_resolution_time() {
     let market_id = BytesN::from_array(&env, &[4u8; 32]);
     let resolution_time = 2000u64;
 
+    register_idle_oracles(&env, &client, 1);
+
     // Register market with resolution time of 2000
-    client.register_market(&market_id, &resolution_time);
+    client.register_market(&market_id, &resolution_time, &2u32);
 
     // Set ledger time BEFORE resolution time
     env.ledger().set_timestamp(1500);
@@ -405,7 +488,7 @@ fn test_submit_attestation_before_resolution_time() {
     let data_hash = BytesN::from_array(&env, &[0u8; 32]);
 
     // This should panic because we're before resolution time
-    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash);
+    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash, &None);
 }
 
 /// Invalid outcome (not 0 or 1) is rejected
@@ -427,8 +510,10 @@ fn test_submit_attestation_invalid_outcome_rejected() {
     let market_id = BytesN::from_array(&env, &[5u8; 32]);
     let resolution_time = 1000u64;
 
+    register_idle_oracles(&env, &client, 1);
+
     // Register market
-    client.register_market(&market_id, &resolution_time);
+    client.register_market(&market_id, &resolution_time, &2u32);
 
     // Set ledger time past resolution time
     env.ledger().set_timestamp(1500);
@@ -436,7 +521,7 @@ fn test_submit_attestation_invalid_outcome_rejected() {
     let data_hash = BytesN::from_array(&env, &[0u8; 32]);
 
     // This should panic because outcome 2 is invalid (only 0 or 1 allowed)
-    client.submit_attestation(&oracle1, &market_id, &2u32, &data_hash);
+    client.submit_attestation(&oracle1, &market_id, &2u32, &data_hash, &None);
 }
 
 /// Verify AttestationSubmitted event is emitted correctly
@@ -457,15 +542,17 @@ fn test_submit_attestation_event_emitted() {
     let market_id = BytesN::from_array(&env, &[6u8; 32]);
     let resolution_time = 1000u64;
 
+    register_idle_oracles(&env, &client, 1);
+
     // Register market
-    client.register_market(&market_id, &resolution_time);
+    client.register_market(&market_id, &resolution_time, &2u32);
 
     // Set ledger time past resolution time
     env.ledger().set_timestamp(1500);
 
     let data_hash = BytesN::from_array(&env, &[0u8; 32]);
 
-    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash);
+    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash, &None);
 
     // Verify event was emitted
     // The event system stores events that can be queried
@@ -495,8 +582,10 @@ fn test_register_market() {
     let market_id = BytesN::from_array(&env, &[7u8; 32]);
     let resolution_time = 3000u64;
 
+    register_idle_oracles(&env, &client, 2);
+
     // Register market
-    client.register_market(&market_id, &resolution_time);
+    client.register_market(&market_id, &resolution_time, &2u32);
 
     // Verify resolution time is stored
     let stored_time = client.get_market_resolution_time(&market_id);
@@ -509,6 +598,32 @@ fn test_register_market() {
     assert_eq!(no_count, 0);
 }
 
+#[test]
+#[should_panic(expected = "not enough oracles")]
+fn test_register_market_requires_enough_oracles() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let client = OracleManagerClient::new(&env, &register_oracle(&env));
+    client.initialize(&Address::generate(&env), &2u32);
+
+    // One oracle can never reach a consensus of two
+    register_idle_oracles(&env, &client, 1);
+    client.register_market(&BytesN::from_array(&env, &[7u8; 32]), &3000u64, &2u32);
+}
+
+#[test]
+fn test_register_market_with_enough_oracles() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let client = OracleManagerClient::new(&env, &register_oracle(&env));
+    client.initialize(&Address::generate(&env), &2u32);
+
+    register_idle_oracles(&env, &client, 2);
+    let market_id = BytesN::from_array(&env, &[7u8; 32]);
+    client.register_market(&market_id, &3000u64, &2u32);
+    assert_eq!(client.get_market_resolution_time(&market_id), Some(3000));
+}
+
 /// Test attestation count tracking for both YES and NO outcomes
 #[test]
 fn test_attestation_count_tracking() {
@@ -532,15 +647,15 @@ fn test_attestation_count_tracking() {
     let resolution_time = 1000u64;
 
     // Register market
-    client.register_market(&market_id, &resolution_time);
+    client.register_market(&market_id, &resolution_time, &2u32);
     env.ledger().set_timestamp(1500);
 
     let data_hash = BytesN::from_array(&env, &[0u8; 32]);
 
     // 2 vote YES, 1 vote NO
-    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash);
-    client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash);
-    client.submit_attestation(&oracle3, &market_id, &0u32, &data_hash);
+    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash, &None);
+    client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash, &None);
+    client.submit_attestation(&oracle3, &market_id, &0u32, &data_hash, &None);
 
     // Verify counts
     let (yes_count, no_count) = client.get_attestation_counts(&market_id);
@@ -602,7 +717,7 @@ fn test_finalize_resolution_integration() {
     );
 
     // Register market in oracle
-    oracle_client.register_market(&market_id_bytes, &resolution_time);
+    oracle_client.register_market(&market_id_bytes, &resolution_time, &2u32);
 
     // Advance time past resolution
     env.ledger().set_timestamp(resolution_time + 10);
@@ -616,8 +731,8 @@ fn test_finalize_resolution_integration() {
 
     // Submit attestations to reach consensus (2 YES, 1 NO)
     let data_hash = BytesN::from_array(&env, &[0u8; 32]);
-    oracle_client.submit_attestation(&oracle1, &market_id_bytes, &1u32, &data_hash);
-    oracle_client.submit_attestation(&oracle2, &market_id_bytes, &1u32, &data_hash);
+    oracle_client.submit_attestation(&oracle1, &market_id_bytes, &1u32, &data_hash, &None);
+    oracle_client.submit_attestation(&oracle2, &market_id_bytes, &1u32, &data_hash, &None);
 
     // Verify consensus reached
     let (reached, outcome) = oracle_client.check_consensus(&market_id_bytes);
@@ -628,7 +743,11 @@ fn test_finalize_resolution_integration() {
     env.ledger().set_timestamp(resolution_time + 604800 + 10);
 
     // Finalize resolution (cross-contract call to market)
-    oracle_client.finalize_resolution(&market_id_bytes, &market_contract_id);
+    oracle_client.finalize_resolution(
+        &market_id_bytes,
+        &market_contract_id,
+        &Address::generate(&env),
+    );
 
     // Verify market is resolved
     let market_state = market_client.get_market_state_value();
@@ -662,18 +781,23 @@ fn test_finalize_resolution_no_consensus() {
     oracle_client.register_oracle(&oracle1, &Symbol::new(&env, "O1"));
 
     let resolution_time = 1000u64;
-    oracle_client.register_market(&market_id_bytes, &resolution_time);
+    register_idle_oracles(&env, &oracle_client, 2);
+    oracle_client.register_market(&market_id_bytes, &resolution_time, &2u32);
 
     // Only 1 attestation (not enough for consensus)
     env.ledger().set_timestamp(resolution_time + 10);
     let data_hash = BytesN::from_array(&env, &[0u8; 32]);
-    oracle_client.submit_attestation(&oracle1, &market_id_bytes, &1u32, &data_hash);
+    oracle_client.submit_attestation(&oracle1, &market_id_bytes, &1u32, &data_hash, &None);
 
     // Advance past dispute period
     env.ledger().set_timestamp(resolution_time + 604800 + 10);
 
     // Should panic: consensus not reached
-    oracle_client.finalize_resolution(&market_id_bytes, &market_contract_id);
+    oracle_client.finalize_resolution(
+        &market_id_bytes,
+        &market_contract_id,
+        &Address::generate(&env),
+    );
 }
 
 /// Test finalize_resolution fails if dispute period not elapsed
@@ -700,19 +824,23 @@ fn test_finalize_resolution_dispute_period_not_elapsed() {
     oracle_client.register_oracle(&oracle2, &Symbol::new(&env, "O2"));
 
     let resolution_time = 1000u64;
-    oracle_client.register_market(&market_id_bytes, &resolution_time);
+    oracle_client.register_market(&market_id_bytes, &resolution_time, &2u32);
 
     // Submit attestations to reach consensus
     env.ledger().set_timestamp(resolution_time + 10);
     let data_hash = BytesN::from_array(&env, &[0u8; 32]);
-    oracle_client.submit_attestation(&oracle1, &market_id_bytes, &1u32, &data_hash);
-    oracle_client.submit_attestation(&oracle2, &market_id_bytes, &1u32, &data_hash);
+    oracle_client.submit_attestation(&oracle1, &market_id_bytes, &1u32, &data_hash, &None);
+    oracle_client.submit_attestation(&oracle2, &market_id_bytes, &1u32, &data_hash, &None);
 
     // Try to finalize before dispute period (only 100 seconds after resolution)
     env.ledger().set_timestamp(resolution_time + 100);
 
     // Should panic: dispute period not elapsed
-    oracle_client.finalize_resolution(&market_id_bytes, &market_contract_id);
+    oracle_client.finalize_resolution(
+        &market_id_bytes,
+        &market_contract_id,
+        &Address::generate(&env),
+    );
 }
 
 /// Test finalize_resolution fails if market not registered
@@ -732,5 +860,9 @@ fn test_finalize_resolution_market_not_registered() {
     oracle_client.initialize(&admin, &2u32);
 
     // Market not registered - should panic
-    oracle_client.finalize_resolution(&market_id_bytes, &market_contract_id);
+    oracle_client.finalize_resolution(
+        &market_id_bytes,
+        &market_contract_id,
+        &Address::generate(&env),
+    );
 }